@@ -0,0 +1,232 @@
+use crate::model::{GroupMemory, HotKey, HotKeyGroup, KeyMemory, MemoryReport};
+use std::collections::HashMap;
+
+/// 默认返回的最大键数量
+pub const DEFAULT_TOP: usize = 100;
+/// 扫描时默认每批键之间的间隔(毫秒)
+pub const DEFAULT_DELAY: u64 = 10;
+
+/// 过期时间分桶的上限(毫秒)与名称, 按顺序匹配
+pub type TtlBuckets = [(i64, &'static str)];
+
+/// 内存分析报告中的过期时间分桶
+pub const MEMORY_TTL_BUCKETS: &TtlBuckets = &[
+    (3_600_000, "<1h"),
+    (86_400_000, "1h-1d"),
+    (604_800_000, "1d-7d"),
+    (2_592_000_000, "7d-30d"),
+    (i64::MAX, ">30d"),
+];
+/// 过期时间分布中的分桶, 更关注即将过期的键
+pub const DISTRIBUTION_TTL_BUCKETS: &TtlBuckets = &[
+    (60_000, "<1m"),
+    (3_600_000, "1m-1h"),
+    (86_400_000, "1h-1d"),
+    (604_800_000, "1d-7d"),
+    (i64::MAX, ">7d"),
+];
+/// 永不过期的键所在的分桶
+const TTL_PERSIST: &str = "persist";
+
+/// 按剩余过期时间(毫秒)分桶
+pub fn ttl_bucket(buckets: &TtlBuckets, pttl: i64) -> &'static str {
+    if pttl < 0 {
+        return TTL_PERSIST;
+    }
+    buckets
+        .iter()
+        .find(|(max, _)| pttl < *max)
+        .map(|(_, name)| *name)
+        .unwrap_or(TTL_PERSIST)
+}
+
+/// 按分隔符取键的前depth段作为命名空间, 没有分隔符的键返回None
+pub fn namespace(key: &str, split: &str, depth: usize) -> Option<String> {
+    if split.is_empty() || depth == 0 {
+        return None;
+    }
+
+    let segments: Vec<&str> = key.split(split).collect();
+    if segments.len() < 2 {
+        return None;
+    }
+
+    // 最后一段是键名本身, 不属于命名空间
+    let depth = depth.min(segments.len() - 1);
+    Some(segments[..depth].join(split))
+}
+
+/// 汇总键的内存占用
+#[derive(Debug, Default)]
+pub struct MemoryAggregator {
+    split: String,
+    depth: usize,
+    top: usize,
+    keys: u64,
+    bytes: u64,
+    by_type: HashMap<String, GroupMemory>,
+    by_encoding: HashMap<String, GroupMemory>,
+    by_prefix: HashMap<String, GroupMemory>,
+    by_ttl: HashMap<String, GroupMemory>,
+    top_keys: Vec<KeyMemory>,
+}
+
+impl MemoryAggregator {
+    pub fn new(split: &str, depth: usize, top: usize) -> Self {
+        Self {
+            split: split.to_string(),
+            depth,
+            top,
+            ..Default::default()
+        }
+    }
+
+    pub fn add(&mut self, key: KeyMemory) {
+        self.keys += 1;
+        self.bytes += key.bytes;
+
+        let (bytes, elements) = (key.bytes, key.elements);
+        add_group(&mut self.by_type, &key.r#type, bytes, elements);
+        if let Some(ref encoding) = key.encoding {
+            let name = format!("{}/{}", key.r#type, encoding);
+            add_group(&mut self.by_encoding, &name, bytes, elements);
+        }
+        let prefix = namespace(&key.key, &self.split, self.depth).unwrap_or_default();
+        add_group(&mut self.by_prefix, &prefix, bytes, elements);
+        add_group(
+            &mut self.by_ttl,
+            ttl_bucket(MEMORY_TTL_BUCKETS, key.ttl),
+            bytes,
+            elements,
+        );
+
+        self.top_keys.push(key);
+        if self.top_keys.len() >= self.top.max(1) * 2 {
+            self.truncate_top();
+        }
+    }
+
+    fn truncate_top(&mut self) {
+        self.top_keys.sort_by_key(|k| std::cmp::Reverse(k.bytes));
+        self.top_keys.truncate(self.top);
+    }
+
+    pub fn keys(&self) -> u64 {
+        self.keys
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    pub fn report(mut self) -> MemoryReport {
+        self.truncate_top();
+        MemoryReport {
+            keys: self.keys,
+            bytes: self.bytes,
+            by_type: sorted_groups(self.by_type),
+            by_encoding: sorted_groups(self.by_encoding),
+            by_prefix: sorted_groups(self.by_prefix),
+            by_ttl: ttl_groups(MEMORY_TTL_BUCKETS, self.by_ttl),
+            top_keys: self.top_keys,
+        }
+    }
+}
+
+pub fn add_group(groups: &mut HashMap<String, GroupMemory>, name: &str, bytes: u64, elements: u64) {
+    let group = groups
+        .entry(name.to_string())
+        .or_insert_with(|| GroupMemory {
+            name: name.to_string(),
+            ..Default::default()
+        });
+    group.keys += 1;
+    group.bytes += bytes;
+    group.elements += elements;
+}
+
+/// 按占用内存从大到小排序, 内存相同时按键数量排序
+pub fn sorted_groups(groups: HashMap<String, GroupMemory>) -> Vec<GroupMemory> {
+    let mut groups: Vec<_> = groups.into_values().collect();
+    groups.sort_by(|a, b| {
+        b.bytes
+            .cmp(&a.bytes)
+            .then_with(|| b.keys.cmp(&a.keys))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    groups
+}
+
+/// 按分桶的顺序排列, 永不过期的排在最后
+pub fn ttl_groups(
+    buckets: &TtlBuckets,
+    mut groups: HashMap<String, GroupMemory>,
+) -> Vec<GroupMemory> {
+    buckets
+        .iter()
+        .map(|(_, name)| *name)
+        .chain([TTL_PERSIST])
+        .filter_map(|name| groups.remove(name))
+        .collect()
+}
+
+/// 汇总热点键, 整体和每个命名空间分别保留访问次数最多的前N个键
+#[derive(Debug, Default)]
+pub struct HotKeyAggregator {
+    split: String,
+    depth: usize,
+    top: usize,
+    keys: HotKeyGroup,
+    by_prefix: HashMap<String, HotKeyGroup>,
+}
+
+impl HotKeyAggregator {
+    pub fn new(split: &str, depth: usize, top: usize) -> Self {
+        Self {
+            split: split.to_string(),
+            depth,
+            top: top.max(1),
+            ..Default::default()
+        }
+    }
+
+    pub fn add(&mut self, key: String, hits: u64) {
+        let prefix = namespace(&key, &self.split, self.depth).unwrap_or_default();
+        let group = self
+            .by_prefix
+            .entry(prefix.clone())
+            .or_insert_with(|| HotKeyGroup {
+                name: prefix,
+                ..Default::default()
+            });
+        add_hot_key(group, key.clone(), hits, self.top);
+        add_hot_key(&mut self.keys, key, hits, self.top);
+    }
+
+    /// 返回整体的前N个键和按访问次数排序的命名空间
+    pub fn report(mut self) -> (Vec<HotKey>, Vec<HotKeyGroup>) {
+        truncate_hot_keys(&mut self.keys.top, self.top);
+
+        let mut groups: Vec<_> = self.by_prefix.into_values().collect();
+        for group in &mut groups {
+            truncate_hot_keys(&mut group.top, self.top);
+        }
+        groups.sort_by(|a, b| b.hits.cmp(&a.hits).then_with(|| a.name.cmp(&b.name)));
+
+        (self.keys.top, groups)
+    }
+}
+
+fn add_hot_key(group: &mut HotKeyGroup, key: String, hits: u64, top: usize) {
+    group.keys += 1;
+    group.hits += hits;
+    group.top.push(HotKey { key, hits });
+    if group.top.len() >= top * 2 {
+        truncate_hot_keys(&mut group.top, top);
+    }
+}
+
+fn truncate_hot_keys(keys: &mut Vec<HotKey>, top: usize) {
+    keys.sort_by(|a, b| b.hits.cmp(&a.hits).then_with(|| a.key.cmp(&b.key)));
+    keys.truncate(top);
+}
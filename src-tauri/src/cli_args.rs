@@ -0,0 +1,178 @@
+/// 按redis-cli的规则拆分命令行参数(与sdssplitargs一致)
+///
+/// - 双引号内支持`\xHH`十六进制转义以及`\n` `\r` `\t` `\b` `\a`等转义
+/// - 单引号内只支持`\'`转义
+/// - 引号必须成对出现, 且右引号之后必须是空白或结尾
+pub fn split_args(line: &str) -> Result<Vec<Vec<u8>>, String> {
+    let bytes = line.as_bytes();
+    let mut args = vec![];
+    let mut i = 0;
+
+    loop {
+        while i < bytes.len() && is_space(bytes[i]) {
+            i += 1;
+        }
+        if i >= bytes.len() {
+            return Ok(args);
+        }
+
+        let mut current = vec![];
+        let mut in_double = false;
+        let mut in_single = false;
+        loop {
+            let Some(&c) = bytes.get(i) else {
+                if in_double || in_single {
+                    return Err("引号不匹配".to_string());
+                }
+                break;
+            };
+
+            if in_double {
+                match c {
+                    b'\\'
+                        if bytes.get(i + 1) == Some(&b'x')
+                            && i + 3 < bytes.len()
+                            && bytes[i + 2].is_ascii_hexdigit()
+                            && bytes[i + 3].is_ascii_hexdigit() =>
+                    {
+                        current.push(hex_value(bytes[i + 2]) * 16 + hex_value(bytes[i + 3]));
+                        i += 3;
+                    }
+                    b'\\' if i + 1 < bytes.len() => {
+                        i += 1;
+                        current.push(match bytes[i] {
+                            b'n' => b'\n',
+                            b'r' => b'\r',
+                            b't' => b'\t',
+                            b'b' => 0x08,
+                            b'a' => 0x07,
+                            other => other,
+                        });
+                    }
+                    b'"' => {
+                        close_quote(bytes, i)?;
+                        i += 1;
+                        break;
+                    }
+                    _ => current.push(c),
+                }
+            } else if in_single {
+                match c {
+                    b'\\' if bytes.get(i + 1) == Some(&b'\'') => {
+                        current.push(b'\'');
+                        i += 1;
+                    }
+                    b'\'' => {
+                        close_quote(bytes, i)?;
+                        i += 1;
+                        break;
+                    }
+                    _ => current.push(c),
+                }
+            } else {
+                match c {
+                    b' ' | b'\n' | b'\r' | b'\t' | b'\0' => break,
+                    b'"' => in_double = true,
+                    b'\'' => in_single = true,
+                    _ => current.push(c),
+                }
+            }
+            i += 1;
+        }
+
+        args.push(current);
+    }
+}
+
+/// 右引号之后必须是空白或结尾
+fn close_quote(bytes: &[u8], i: usize) -> Result<(), String> {
+    match bytes.get(i + 1) {
+        Some(&next) if !is_space(next) => Err("引号后必须是空格".to_string()),
+        _ => Ok(()),
+    }
+}
+
+/// 与C的isspace一致, 包括`\v`和`\f`; 只用于跳过参数之间的空白和检查右引号之后的字符,
+/// 不加引号的参数只在空格, `\n`, `\r`, `\t`和`\0`处结束
+fn is_space(c: u8) -> bool {
+    matches!(c, b' ' | b'\t' | b'\n' | 0x0b | 0x0c | b'\r')
+}
+
+fn hex_value(c: u8) -> u8 {
+    match c {
+        b'0'..=b'9' => c - b'0',
+        b'a'..=b'f' => c - b'a' + 10,
+        _ => c - b'A' + 10,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split_args;
+
+    fn args(list: &[&str]) -> Result<Vec<Vec<u8>>, String> {
+        Ok(list.iter().map(|arg| arg.as_bytes().to_vec()).collect())
+    }
+
+    #[test]
+    fn plain() {
+        assert_eq!(
+            split_args("  set  key value "),
+            args(&["set", "key", "value"])
+        );
+        assert_eq!(split_args(""), args(&[]));
+    }
+
+    #[test]
+    fn isspace() {
+        // \v和\f可以出现在参数之间和右引号之后, 但不会结束不加引号的参数
+        assert_eq!(
+            split_args("get\x0bkey\x0cend"),
+            args(&["get\x0bkey\x0cend"])
+        );
+        assert_eq!(split_args("\x0c get\x0b"), args(&["get\x0b"]));
+        assert_eq!(split_args("\"a\"\x0b'b'"), args(&["a", "b"]));
+    }
+
+    #[test]
+    fn double_quotes() {
+        assert_eq!(
+            split_args(r#"set "my key" "a\"b\n\\""#),
+            args(&["set", "my key", "a\"b\n\\"])
+        );
+        assert_eq!(split_args(r#"set k"ey" v"#), args(&["set", "key", "v"]));
+        assert_eq!(split_args(r#""""#), args(&[""]));
+    }
+
+    #[test]
+    fn hex_escape() {
+        assert_eq!(
+            split_args(r#"set "\x00\xfF\x41""#),
+            Ok(vec![b"set".to_vec(), vec![0x00, 0xff, b'A']])
+        );
+        // 不完整的十六进制转义按普通转义处理
+        assert_eq!(split_args(r#""\x4""#), args(&["x4"]));
+    }
+
+    #[test]
+    fn single_quotes() {
+        assert_eq!(
+            split_args(r#"set 'it\'s' '\n'"#),
+            args(&["set", "it's", "\\n"])
+        );
+    }
+
+    #[test]
+    fn unbalanced_quotes() {
+        assert!(split_args(r#"set "key"#).is_err());
+        assert!(split_args("set 'key").is_err());
+        assert!(split_args(r#"set "key\""#).is_err());
+    }
+
+    #[test]
+    fn closing_quote_followed_by_text() {
+        assert!(split_args(r#"set "key"value"#).is_err());
+        assert!(split_args("set 'key'value").is_err());
+        assert_eq!(split_args("get \"key\"\t"), args(&["get", "key"]));
+    }
+}
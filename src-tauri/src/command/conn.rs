@@ -1,4 +1,7 @@
-use crate::{config::RedisConfig, error::Result, CmdLog, History, RedisConnection, RedisState};
+use crate::{
+    config::RedisConfig, debug::DebugState, error::Result, terminal::TerminalState, CmdLog,
+    History, OfflineState, RedisConnection, RedisState,
+};
 use redis::{aio::ConnectionLike, InfoDict};
 use serde_json::json;
 use std::collections::HashMap;
@@ -43,10 +46,14 @@ pub async fn connection(
 
 /// 判断是否已连接
 #[tauri::command]
-#[instrument(skip(state))]
-pub async fn is_connection(state: State<'_, RedisState>, id: String) -> Result<bool> {
+#[instrument(skip(state, offline))]
+pub async fn is_connection(
+    state: State<'_, RedisState>,
+    offline: State<'_, OfflineState>,
+    id: String,
+) -> Result<bool> {
     let redis_state = state.0.lock().await;
-    let is_connection = redis_state.is_connection(&id);
+    let is_connection = redis_state.is_connection(&id) || offline.is_offline(&id);
 
     info!(?is_connection, "是否已连接");
     Ok(is_connection)
@@ -54,12 +61,17 @@ pub async fn is_connection(state: State<'_, RedisState>, id: String) -> Result<b
 
 /// ping
 #[tauri::command]
-#[instrument(skip(state, history))]
+#[instrument(skip(state, offline, history))]
 pub async fn ping(
     state: State<'_, RedisState>,
+    offline: State<'_, OfflineState>,
     history: State<'_, History>,
     id: String,
 ) -> Result<()> {
+    if offline.is_offline(&id) {
+        return Ok(());
+    }
+
     let mut redis_state = state.0.lock().await;
     let (con, config) = redis_state.get_con_and_config(&id)?;
 
@@ -74,13 +86,18 @@ pub async fn ping(
 
 /// change db
 #[tauri::command]
-#[instrument(skip(state, history))]
+#[instrument(skip(state, offline, history))]
 pub async fn change_db(
     state: State<'_, RedisState>,
+    offline: State<'_, OfflineState>,
     history: State<'_, History>,
     id: String,
     db: u16,
 ) -> Result<()> {
+    if offline.is_offline(&id) {
+        return Ok(());
+    }
+
     let mut redis_state = state.0.lock().await;
     let (con, config) = redis_state.get_con_and_config(&id)?;
 
@@ -96,10 +113,19 @@ pub async fn change_db(
 
 /// 断开连接
 #[tauri::command]
-#[instrument(skip(state))]
-pub async fn dis_connection(state: State<'_, RedisState>, id: String) -> Result<()> {
+#[instrument(skip(state, offline, terminals, debug))]
+pub async fn dis_connection(
+    state: State<'_, RedisState>,
+    offline: State<'_, OfflineState>,
+    terminals: State<'_, TerminalState>,
+    debug: State<'_, DebugState>,
+    id: String,
+) -> Result<()> {
     let mut redis_state = state.0.lock().await;
     redis_state.remove_con(&id)?;
+    offline.0.write().unwrap().remove(&id);
+    terminals.remove_by_id(Some(&id));
+    debug.remove_by_id(Some(&id));
 
     info!(id, "断开连接成功");
     Ok(())
@@ -107,10 +133,18 @@ pub async fn dis_connection(state: State<'_, RedisState>, id: String) -> Result<
 
 /// 断开所有连接
 #[tauri::command]
-#[instrument(skip(state))]
-pub async fn dis_connection_all(state: State<'_, RedisState>) -> Result<()> {
+#[instrument(skip(state, offline, terminals, debug))]
+pub async fn dis_connection_all(
+    state: State<'_, RedisState>,
+    offline: State<'_, OfflineState>,
+    terminals: State<'_, TerminalState>,
+    debug: State<'_, DebugState>,
+) -> Result<()> {
     let mut redis_state = state.0.lock().await;
     redis_state.remove_con_all()?;
+    offline.0.write().unwrap().clear();
+    terminals.remove_by_id(None);
+    debug.remove_by_id(None);
 
     info!("断开所有连接成功");
     Ok(())
@@ -118,12 +152,17 @@ pub async fn dis_connection_all(state: State<'_, RedisState>) -> Result<()> {
 
 /// 获取redis客户端信息
 #[tauri::command]
-#[instrument(skip(state, history))]
+#[instrument(skip(state, offline, history))]
 pub async fn get_info(
     state: State<'_, RedisState>,
+    offline: State<'_, OfflineState>,
     history: State<'_, History>,
     id: String,
 ) -> Result<serde_json::Value> {
+    if let Some(instance) = offline.get(&id) {
+        return Ok(instance.info());
+    }
+
     let mut redis_state = state.0.lock().await;
     let (con, config) = redis_state.get_con_and_config(&id)?;
 
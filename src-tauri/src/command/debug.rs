@@ -0,0 +1,111 @@
+use crate::{
+    debug::{DebugState, LdbSession, DEBUG_EVENT},
+    error::Result,
+    model::{DebugCommand, DebugReply, DebugRequest, DebugSessionInfo},
+    History, RedisState,
+};
+use anyhow::Context;
+use tauri::{State, Window};
+use tracing::{info, instrument, warn};
+
+/// 使用独立的连接开始调试lua脚本, 返回脚本停在第一行时的输出
+#[tauri::command]
+#[instrument(skip(window, state, history, debug, request), fields(mode = ?request.mode))]
+pub async fn debug_start(
+    window: Window,
+    state: State<'_, RedisState>,
+    history: State<'_, History>,
+    debug: State<'_, DebugState>,
+    id: String,
+    db: u8,
+    request: DebugRequest,
+) -> Result<DebugReply> {
+    let config = state.0.lock().await.get_config(&id)?;
+    if config.cluster {
+        return Err("集群模式下不支持调试脚本".into());
+    }
+
+    history.add_log(format!("[db{db}] script debug {:?}", request.mode), &config);
+
+    let (session, mut reply) =
+        tauri::async_runtime::spawn_blocking(move || LdbSession::start(&config, &id, db, &request))
+            .await??;
+
+    // 脚本可能在第一行之前就已经结束, 例如运行时错误
+    if !reply.ended {
+        reply.session_id = debug.insert(session);
+    }
+    emit(&window, &reply);
+
+    info!(session_id = reply.session_id, "开始调试脚本");
+    Ok(reply)
+}
+
+/// 发送调试命令, 会话结束或连接断开后删除会话
+#[tauri::command]
+#[instrument(skip(window, debug))]
+pub async fn debug_command(
+    window: Window,
+    debug: State<'_, DebugState>,
+    session_id: String,
+    command: DebugCommand,
+) -> Result<DebugReply> {
+    let session = debug.get(&session_id).context("调试会话不存在")?;
+    let res = tauri::async_runtime::spawn_blocking(move || session.lock().unwrap().send(&command))
+        .await?;
+
+    match res {
+        Ok(reply) => {
+            if reply.ended {
+                debug.remove(&session_id);
+                info!(session_id, "调试结束");
+            }
+            emit(&window, &reply);
+            Ok(reply)
+        }
+        Err(err) => {
+            debug.remove(&session_id);
+            warn!(session_id, error = err.message(), "调试连接断开");
+            Err(err)
+        }
+    }
+}
+
+/// 正在进行的调试会话
+#[tauri::command]
+#[instrument(skip(debug))]
+pub async fn debug_sessions(debug: State<'_, DebugState>) -> Result<Vec<DebugSessionInfo>> {
+    Ok(debug.list())
+}
+
+/// 停止脚本并结束会话
+#[tauri::command]
+#[instrument(skip(window, debug))]
+pub async fn debug_stop(
+    window: Window,
+    debug: State<'_, DebugState>,
+    session_id: String,
+) -> Result<bool> {
+    let Some(session) = debug.get(&session_id) else {
+        return Ok(false);
+    };
+    debug.remove(&session_id);
+
+    let res = tauri::async_runtime::spawn_blocking(move || {
+        session.lock().unwrap().send(&DebugCommand::Abort)
+    })
+    .await?;
+    match res {
+        Ok(reply) => emit(&window, &reply),
+        Err(err) => warn!(session_id, error = err.message(), "停止调试失败"),
+    }
+
+    info!(session_id, "停止调试");
+    Ok(true)
+}
+
+fn emit(window: &Window, reply: &DebugReply) {
+    if let Err(err) = window.emit(DEBUG_EVENT, reply.clone()) {
+        warn!(?err, session_id = reply.session_id, "发送调试事件失败");
+    }
+}
@@ -0,0 +1,163 @@
+use crate::{
+    analysis::DEFAULT_DELAY,
+    config::RedisConfig,
+    dump::dump_keys,
+    error::Result,
+    job::{spawn_job, JobContext, Jobs},
+    model::{DeleteOptions, DeleteProgress, KeyPreview},
+    scan::{preview_keys, KeyScanner, SCAN_COUNT},
+    slot::key_slot,
+    undo::{UndoEntry, UndoState},
+    History, RedisState, SharedConnection,
+};
+use redis::RedisResult;
+use std::{collections::BTreeMap, time::Duration};
+use tauri::{Manager, State, Window};
+use tracing::{info, instrument};
+
+use super::is_unsupported_error;
+
+/// 默认每次UNLINK的键数量
+const DEFAULT_BATCH_SIZE: usize = 100;
+/// 默认预览的键数量
+const DEFAULT_PREVIEW: usize = 100;
+
+/// 预览匹配的键, 返回总数和部分键
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn preview_match_keys(
+    state: State<'_, RedisState>,
+    id: String,
+    db: u8,
+    pattern: String,
+    limit: Option<usize>,
+) -> Result<KeyPreview> {
+    let config = state.0.lock().await.get_config(&id)?;
+    let con = SharedConnection::open(&config, db).await?;
+
+    let (count, keys) =
+        preview_keys(&config, &con, &pattern, limit.unwrap_or(DEFAULT_PREVIEW)).await?;
+
+    info!(count, "预览匹配的key成功");
+    Ok(KeyPreview { count, keys })
+}
+
+/// 在后台分批删除匹配的键, 返回任务id
+#[tauri::command]
+#[instrument(skip(window, state, history, jobs))]
+pub async fn delete_match_keys(
+    window: Window,
+    state: State<'_, RedisState>,
+    history: State<'_, History>,
+    jobs: State<'_, Jobs>,
+    id: String,
+    db: u8,
+    options: DeleteOptions,
+) -> Result<String> {
+    let config = state.0.lock().await.get_config(&id)?;
+    let con = SharedConnection::open(&config, db).await?;
+
+    history.add_log(format!("[db{db}] unlink {}", options.pattern), &config);
+
+    let undo = window.state::<UndoState>().inner().clone();
+    let job_id = spawn_job(window, &jobs, "delete", |ctx| async move {
+        let mut entry = UndoEntry::new("unlink", db);
+        let res = delete_keys(Some(&ctx), &config, con, &options, Some(&mut entry)).await;
+        undo.push(&id, entry);
+        res
+    });
+
+    info!(job_id, "开始删除匹配的key");
+    Ok(job_id)
+}
+
+/// 扫描并分批删除匹配的键, 集群模式下同一批次中的键按槽分组
+///
+/// 传入undo时在删除前保存键的DUMP, 超过撤销记录的上限后不再保存
+pub async fn delete_keys(
+    ctx: Option<&JobContext>,
+    config: &RedisConfig,
+    con: SharedConnection,
+    options: &DeleteOptions,
+    mut undo: Option<&mut UndoEntry>,
+) -> Result<DeleteProgress> {
+    if options.pattern.is_empty() {
+        return Err("匹配模式不能为空".into());
+    }
+
+    let batch_size = options.batch_size.unwrap_or(DEFAULT_BATCH_SIZE).max(1);
+    let delay = Duration::from_millis(options.delay.unwrap_or(DEFAULT_DELAY));
+
+    let mut unlink = true;
+    let mut progress = DeleteProgress::default();
+    let mut scanner = KeyScanner::new(config, &con, &options.pattern, SCAN_COUNT).await?;
+    while let Some((mut node, keys)) = scanner.next_batch().await? {
+        for keys in keys.chunks(batch_size) {
+            if ctx.is_some_and(|ctx| ctx.is_cancelled()) {
+                return Ok(progress);
+            }
+
+            progress.scanned += keys.len() as u64;
+            if let Some(max_keys) = options.max_keys {
+                if progress.scanned > max_keys {
+                    return Err(format!(
+                        "匹配的key超过{max_keys}个, 已删除{}个后停止",
+                        progress.deleted
+                    )
+                    .into());
+                }
+            }
+
+            if let Some(entry) = undo.as_deref_mut().filter(|entry| !entry.is_full()) {
+                let dumps = dump_keys(&mut node, keys).await?;
+                for (key, dump) in keys.iter().zip(dumps) {
+                    if let Some(dump) = dump {
+                        entry.push(key.clone(), dump);
+                    }
+                }
+            }
+
+            let groups: Vec<Vec<&String>> = if config.cluster {
+                let mut slots: BTreeMap<u16, Vec<&String>> = BTreeMap::new();
+                for key in keys {
+                    slots.entry(key_slot(key.as_bytes())).or_default().push(key);
+                }
+                slots.into_values().collect()
+            } else {
+                vec![keys.iter().collect()]
+            };
+
+            for group in groups {
+                progress.deleted += unlink_keys(&mut node, &group, &mut unlink).await?;
+            }
+
+            if let Some(ctx) = ctx {
+                ctx.progress(&progress);
+            }
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+
+    info!(?progress, "删除匹配的key完成");
+    Ok(progress)
+}
+
+/// 使用UNLINK在后台释放内存, 服务器不支持时回退为DEL
+async fn unlink_keys(
+    con: &mut SharedConnection,
+    keys: &[&String],
+    unlink: &mut bool,
+) -> Result<u64> {
+    if *unlink {
+        let res: RedisResult<u64> = redis::cmd("UNLINK").arg(keys).query_async(con).await;
+        match res {
+            Ok(deleted) => return Ok(deleted),
+            Err(err) if is_unsupported_error(&err) => *unlink = false,
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    Ok(redis::cmd("DEL").arg(keys).query_async(con).await?)
+}
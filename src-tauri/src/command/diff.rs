@@ -0,0 +1,505 @@
+use crate::{
+    analysis::DEFAULT_DELAY,
+    config::RedisConfig,
+    diff::{compare_details, diff_values},
+    dump::{dump_key, KeyDump},
+    error::Result,
+    job::{spawn_job, JobContext, Jobs},
+    model::{
+        DiffKind, DiffOptions, DiffProgress, KeyComparison, KeyContentDetail, KeyDiff, KeyError,
+        KeyLocation, SyncOptions, SyncProgress,
+    },
+    scan::{KeyScanner, SCAN_COUNT},
+    undo::{UndoEntry, UndoState},
+    value::{read_record, record_cmds},
+    History, OfflineState, RedisState, SharedConnection,
+};
+use futures::{future::join_all, stream, StreamExt};
+use redis::{AsyncCommands, RedisError, RedisResult};
+use std::time::Duration;
+use tauri::{Manager, State, Window};
+use tracing::{info, instrument, warn};
+
+use super::{load_key_detail, select_db};
+
+/// 默认允许的过期时间误差(毫秒), 两次读取之间过期时间会变化
+const DEFAULT_TTL_TOLERANCE: i64 = 1000;
+/// 同时比较的键数量
+const CONCURRENCY: usize = 8;
+/// 每批同步的键数量
+const SYNC_BATCH_SIZE: usize = 50;
+/// 进度中最多保留的失败记录
+const MAX_FAILURES: usize = 100;
+
+/// 在后台比较两个连接或数据库中匹配的键, 差异通过任务数据事件返回, 返回任务id
+#[tauri::command]
+#[instrument(skip(window, state, history, jobs))]
+pub async fn diff_keys(
+    window: Window,
+    state: State<'_, RedisState>,
+    history: State<'_, History>,
+    jobs: State<'_, Jobs>,
+    options: DiffOptions,
+) -> Result<String> {
+    let (source_config, target_config) = {
+        let redis_state = state.0.lock().await;
+        (
+            redis_state.get_config(&options.source_id)?,
+            redis_state.get_config(&options.target_id)?,
+        )
+    };
+
+    let source = SharedConnection::open(&source_config, options.source_db).await?;
+    let target = SharedConnection::open(&target_config, options.target_db).await?;
+
+    history.add_log(
+        format!(
+            "diff db{} {} <=> [{}] db{}",
+            options.source_db, options.pattern, target_config.name, options.target_db
+        ),
+        &source_config,
+    );
+
+    let job_id = spawn_job(window, &jobs, "diff", |ctx| async move {
+        let mut progress = DiffProgress::default();
+        diff_source(
+            &ctx,
+            &source_config,
+            &source,
+            &target,
+            &options,
+            &mut progress,
+        )
+        .await?;
+        diff_target(
+            &ctx,
+            &target_config,
+            &source,
+            &target,
+            &options,
+            &mut progress,
+        )
+        .await?;
+        info!(job_id = ctx.id(), ?progress, "比较完成");
+        Ok(progress)
+    });
+
+    info!(job_id, "开始比较");
+    Ok(job_id)
+}
+
+/// 扫描源中的键, 与目标中的同名键比较
+async fn diff_source(
+    ctx: &JobContext,
+    config: &RedisConfig,
+    source: &SharedConnection,
+    target: &SharedConnection,
+    options: &DiffOptions,
+    progress: &mut DiffProgress,
+) -> Result<()> {
+    let delay = Duration::from_millis(options.delay.unwrap_or(DEFAULT_DELAY));
+    let scan_count = options.scan_count.unwrap_or(SCAN_COUNT).max(1);
+    let tolerance = options
+        .ttl_tolerance
+        .unwrap_or(DEFAULT_TTL_TOLERANCE)
+        .max(0);
+
+    let mut scanner = KeyScanner::new(config, source, &options.pattern, scan_count).await?;
+    while let Some((node, keys)) = scanner.next_batch().await? {
+        if ctx.is_cancelled() {
+            return Ok(());
+        }
+
+        progress.source_scanned += keys.len() as u64;
+        let results: Vec<_> = stream::iter(keys)
+            .map(|key| {
+                let mut node = node.clone();
+                let mut target = target.clone();
+                async move {
+                    let res = compare_key(&mut node, &mut target, &key, tolerance).await;
+                    (key, res)
+                }
+            })
+            .buffered(CONCURRENCY)
+            .collect()
+            .await;
+
+        let mut diffs = vec![];
+        for (key, res) in results {
+            match res {
+                Ok(Some(diff)) => {
+                    count_diff(progress, diff.kind);
+                    diffs.push(diff);
+                }
+                Ok(None) => progress.equal += 1,
+                Err(err) => {
+                    warn!(?err, key, "比较key失败");
+                    progress.failed += 1;
+                }
+            }
+        }
+
+        if !diffs.is_empty() {
+            ctx.send(diffs);
+        }
+        ctx.progress(progress);
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    Ok(())
+}
+
+/// 扫描目标中的键, 找出源中不存在的键
+async fn diff_target(
+    ctx: &JobContext,
+    config: &RedisConfig,
+    source: &SharedConnection,
+    target: &SharedConnection,
+    options: &DiffOptions,
+    progress: &mut DiffProgress,
+) -> Result<()> {
+    let delay = Duration::from_millis(options.delay.unwrap_or(DEFAULT_DELAY));
+    let scan_count = options.scan_count.unwrap_or(SCAN_COUNT).max(1);
+
+    let mut scanner = KeyScanner::new(config, target, &options.pattern, scan_count).await?;
+    while let Some((node, keys)) = scanner.next_batch().await? {
+        if ctx.is_cancelled() {
+            return Ok(());
+        }
+
+        progress.target_scanned += keys.len() as u64;
+        // 集群模式下同一批次的键可能在源的不同节点上, 逐个查询
+        let exists = join_all(keys.iter().map(|key| {
+            let mut source = source.clone();
+            async move { source.exists::<_, bool>(key).await }
+        }))
+        .await;
+
+        let missing: Vec<_> = keys
+            .into_iter()
+            .zip(exists)
+            .filter_map(|(key, exists)| match exists {
+                Ok(exists) => (!exists).then_some(key),
+                Err(err) => {
+                    warn!(?err, key, "比较key失败");
+                    progress.failed += 1;
+                    None
+                }
+            })
+            .collect();
+
+        let infos = join_all(missing.iter().map(|key| {
+            let mut node = node.clone();
+            async move {
+                redis::pipe()
+                    .key_type(key)
+                    .pttl(key)
+                    .query_async::<_, (String, i64)>(&mut node)
+                    .await
+            }
+        }))
+        .await;
+
+        let mut diffs = vec![];
+        for (key, info) in missing.into_iter().zip(infos) {
+            let (target_type, target_ttl) = match info {
+                // 扫描之后键被删除或已过期
+                Ok((typ, _)) if typ == "none" => continue,
+                Ok((typ, ttl)) => (Some(typ), Some(ttl)),
+                Err(_) => (None, None),
+            };
+            progress.only_in_target += 1;
+            diffs.push(KeyDiff {
+                key,
+                kind: DiffKind::OnlyInTarget,
+                source_type: None,
+                target_type,
+                source_ttl: None,
+                target_ttl,
+                diff: None,
+            });
+        }
+
+        if !diffs.is_empty() {
+            ctx.send(diffs);
+        }
+        ctx.progress(progress);
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    Ok(())
+}
+
+/// 比较两边的同名键, 相同或源中的键已不存在时返回None
+async fn compare_key(
+    source: &mut SharedConnection,
+    target: &mut SharedConnection,
+    key: &str,
+    tolerance: i64,
+) -> RedisResult<Option<KeyDiff>> {
+    let (source_record, target_record) =
+        futures::try_join!(read_record(source, key), read_record(target, key))?;
+    let Some(source_record) = source_record else {
+        return Ok(None);
+    };
+
+    let mut diff = KeyDiff {
+        key: key.to_string(),
+        kind: DiffKind::OnlyInSource,
+        source_type: Some(source_record.value.type_name().to_string()),
+        target_type: None,
+        source_ttl: Some(source_record.pttl),
+        target_ttl: None,
+        diff: None,
+    };
+    let Some(target_record) = target_record else {
+        return Ok(Some(diff));
+    };
+
+    diff.target_type = Some(target_record.value.type_name().to_string());
+    diff.target_ttl = Some(target_record.pttl);
+    diff.kind = if diff.source_type != diff.target_type {
+        DiffKind::Type
+    } else if let Some(value_diff) = diff_values(&source_record.value, &target_record.value) {
+        diff.diff = Some(value_diff);
+        DiffKind::Value
+    } else if !ttl_equal(source_record.pttl, target_record.pttl, tolerance) {
+        DiffKind::Ttl
+    } else {
+        return Ok(None);
+    };
+
+    Ok(Some(diff))
+}
+
+/// -1表示永不过期, 只有两边都会过期时才允许误差
+fn ttl_equal(source: i64, target: i64, tolerance: i64) -> bool {
+    if source < 0 || target < 0 {
+        return source == target;
+    }
+    (source - target).abs() <= tolerance
+}
+
+fn count_diff(progress: &mut DiffProgress, kind: DiffKind) {
+    match kind {
+        DiffKind::OnlyInSource => progress.only_in_source += 1,
+        DiffKind::OnlyInTarget => progress.only_in_target += 1,
+        DiffKind::Type => progress.type_diff += 1,
+        DiffKind::Ttl => progress.ttl_diff += 1,
+        DiffKind::Value => progress.value_diff += 1,
+    }
+}
+
+/// 在后台将源中的键同步到目标, 源中不存在的键会从目标中删除, 返回任务id
+///
+/// 覆盖或删除之前保存目标中的旧值, 可以通过撤销恢复
+#[tauri::command]
+#[instrument(skip(window, state, history, jobs, options), fields(keys = options.keys.len()))]
+pub async fn sync_keys(
+    window: Window,
+    state: State<'_, RedisState>,
+    history: State<'_, History>,
+    jobs: State<'_, Jobs>,
+    options: SyncOptions,
+) -> Result<String> {
+    let (source_config, target_config) = {
+        let redis_state = state.0.lock().await;
+        (
+            redis_state.get_config(&options.source_id)?,
+            redis_state.get_config(&options.target_id)?,
+        )
+    };
+
+    let source = SharedConnection::open(&source_config, options.source_db).await?;
+    let target = SharedConnection::open(&target_config, options.target_db).await?;
+
+    history.add_log(
+        format!(
+            "sync db{} {} keys => [{}] db{}",
+            options.source_db,
+            options.keys.len(),
+            target_config.name,
+            options.target_db
+        ),
+        &source_config,
+    );
+
+    let undo = window.state::<UndoState>().inner().clone();
+    let job_id = spawn_job(window, &jobs, "sync", |ctx| async move {
+        let mut entry = UndoEntry::new("sync", options.target_db);
+        let res = sync(
+            &ctx,
+            target_config.cluster,
+            source,
+            target,
+            &options,
+            &mut entry,
+        )
+        .await;
+        undo.push(&options.target_id, entry);
+        res
+    });
+
+    info!(job_id, "开始同步");
+    Ok(job_id)
+}
+
+async fn sync(
+    ctx: &JobContext,
+    cluster: bool,
+    source: SharedConnection,
+    target: SharedConnection,
+    options: &SyncOptions,
+    undo: &mut UndoEntry,
+) -> Result<SyncProgress> {
+    let mut progress = SyncProgress {
+        total: options.keys.len() as u64,
+        ..Default::default()
+    };
+
+    for keys in options.keys.chunks(SYNC_BATCH_SIZE) {
+        if ctx.is_cancelled() {
+            return Ok(progress);
+        }
+
+        let results: Vec<_> = stream::iter(keys.iter().cloned())
+            .map(|key| {
+                let mut source = source.clone();
+                let mut target = target.clone();
+                async move {
+                    let res = sync_key(&mut source, &mut target, &key, cluster).await;
+                    (key, res)
+                }
+            })
+            .buffered(CONCURRENCY)
+            .collect()
+            .await;
+
+        for (key, res) in results {
+            match res {
+                Ok((written, dump)) => {
+                    if written {
+                        progress.written += 1;
+                    } else {
+                        progress.deleted += 1;
+                    }
+                    if let Some(dump) = dump {
+                        undo.push(key, dump);
+                    }
+                }
+                Err(err) => {
+                    progress.failed += 1;
+                    if progress.failures.len() < MAX_FAILURES {
+                        progress.failures.push(KeyError {
+                            key,
+                            message: err.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+
+        ctx.progress(&progress);
+    }
+
+    info!(job_id = ctx.id(), ?progress, "同步完成");
+    Ok(progress)
+}
+
+/// 用源中的值覆盖目标, 源中不存在时删除目标中的键
+///
+/// 优先使用DUMP/RESTORE, 可以复制非utf8编码的值; 目标不接受源的DUMP格式(例如版本不同)时按类型重建
+///
+/// 返回是否写入以及目标中原来的值
+async fn sync_key(
+    source: &mut SharedConnection,
+    target: &mut SharedConnection,
+    key: &str,
+    cluster: bool,
+) -> RedisResult<(bool, Option<KeyDump>)> {
+    let dump = dump_key(target, key).await?;
+    let Some(source_dump) = dump_key(source, key).await? else {
+        target.del::<_, ()>(key).await?;
+        return Ok((false, dump));
+    };
+    match source_dump
+        .restore_cmd(key, true)
+        .query_async::<_, ()>(target)
+        .await
+    {
+        Ok(()) => return Ok((true, dump)),
+        Err(err) if !incompatible_payload(&err) => return Err(err),
+        Err(_) => {}
+    }
+
+    let Some(record) = read_record(source, key).await? else {
+        target.del::<_, ()>(key).await?;
+        return Ok((false, dump));
+    };
+
+    let mut pipe = redis::pipe();
+    // 集群模式下的事务没有键, 无法路由到对应节点; 所有命令属于同一个键, 按顺序执行即可
+    if !cluster {
+        pipe.atomic();
+    }
+    for cmd in record_cmds(&record) {
+        pipe.add_command(cmd).ignore();
+    }
+    pipe.query_async::<_, ()>(target).await?;
+
+    Ok((true, dump))
+}
+
+/// 目标的rdb版本低于源或校验失败时RESTORE返回的错误
+fn incompatible_payload(err: &RedisError) -> bool {
+    err.detail()
+        .is_some_and(|detail| detail.contains("DUMP payload") || detail.contains("Bad data format"))
+}
+
+/// 并排比较两个键(可以在不同的连接或数据库), 返回两边的值和按类型比较的结果
+///
+/// stream只比较最新的消息, 与键详情中展示的一致
+#[tauri::command]
+#[instrument(skip(state, offline, history))]
+pub async fn compare_keys(
+    state: State<'_, RedisState>,
+    offline: State<'_, OfflineState>,
+    history: State<'_, History>,
+    source: KeyLocation,
+    target: KeyLocation,
+) -> Result<KeyComparison> {
+    let source = read_detail(&state, &offline, &history, source).await?;
+    let target = read_detail(&state, &offline, &history, target).await?;
+
+    let diff = compare_details(&source, &target);
+    let equal = diff.is_equal();
+
+    info!(equal, "比较key成功");
+    Ok(KeyComparison {
+        source,
+        target,
+        equal,
+        diff,
+    })
+}
+
+async fn read_detail(
+    state: &RedisState,
+    offline: &OfflineState,
+    history: &History,
+    location: KeyLocation,
+) -> Result<KeyContentDetail> {
+    if let Some(instance) = offline.get(&location.id) {
+        return instance.key_detail(location.db, &location.key, None);
+    }
+
+    let mut redis_state = state.0.lock().await;
+    let (con, config) = redis_state.get_con_and_config(&location.id)?;
+    select_db(config, location.db, con, history).await?;
+
+    load_key_detail(con, config, history, location.key, None).await
+}
@@ -0,0 +1,181 @@
+use crate::{
+    config::RedisConfig,
+    error::Result,
+    model::{ExportFormat, ExportOptions, ExportReport, KeyError, KeyRecord, KeyValue},
+    scan::{KeyScanner, SCAN_COUNT},
+    value::{read_record, record_cmds},
+    History, RedisState, SharedConnection,
+};
+use redis::RedisResult;
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+};
+use tauri::State;
+use tracing::{info, instrument, warn};
+
+const MAX_FAILURES: usize = 100;
+
+/// 导出键到本地文件
+#[tauri::command]
+#[instrument(skip(state, history))]
+pub async fn export_keys(
+    state: State<'_, RedisState>,
+    history: State<'_, History>,
+    id: String,
+    db: u8,
+    options: ExportOptions,
+) -> Result<ExportReport> {
+    let config = state.0.lock().await.get_config(&id)?;
+    let con = SharedConnection::open(&config, db).await?;
+
+    let file = File::create(&options.path)?;
+    let writer = RecordWriter::new(options.format, BufWriter::new(file))?;
+
+    let report = match export(&config, con, writer, &options).await {
+        Ok(report) => report,
+        Err(err) => {
+            // 不保留导出到一半的文件
+            if let Err(err) = std::fs::remove_file(&options.path) {
+                warn!(?err, path = options.path, "删除导出文件失败");
+            }
+            return Err(err);
+        }
+    };
+
+    log_export(&history, &config, db, &options);
+    info!(?report, "导出完成");
+
+    Ok(report)
+}
+
+async fn export<W: Write>(
+    config: &RedisConfig,
+    mut con: SharedConnection,
+    mut writer: RecordWriter<W>,
+    options: &ExportOptions,
+) -> Result<ExportReport> {
+    let mut report = ExportReport::default();
+
+    match options.keys {
+        Some(ref keys) if !keys.is_empty() => {
+            for key in keys {
+                let record = read_record(&mut con, key).await;
+                writer.write(key, record, &mut report)?;
+            }
+        }
+        _ => {
+            let pattern = options.pattern.as_deref().unwrap_or("*");
+            let mut scanner = KeyScanner::new(config, &con, pattern, SCAN_COUNT).await?;
+            while let Some((mut node, keys)) = scanner.next_batch().await? {
+                for key in keys {
+                    let record = read_record(&mut node, &key).await;
+                    writer.write(&key, record, &mut report)?;
+                }
+            }
+        }
+    }
+    writer.flush()?;
+
+    Ok(report)
+}
+
+fn log_export(history: &History, config: &RedisConfig, db: u8, options: &ExportOptions) {
+    let target = match options.keys {
+        Some(ref keys) if !keys.is_empty() => keys.join(" "),
+        _ => options.pattern.clone().unwrap_or_else(|| "*".to_string()),
+    };
+    history.add_log(
+        format!("export db{db} {target} => {}", options.path),
+        config,
+    );
+}
+
+/// 按导出格式写入键
+enum RecordWriter<W: Write> {
+    Json(W),
+    Csv(Box<csv::Writer<W>>),
+    Resp(W),
+}
+
+impl<W: Write> RecordWriter<W> {
+    fn new(format: ExportFormat, writer: W) -> Result<Self> {
+        let writer = match format {
+            ExportFormat::Json => RecordWriter::Json(writer),
+            ExportFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(writer);
+                writer.write_record(["key", "type", "ttl", "field", "value"])?;
+                RecordWriter::Csv(Box::new(writer))
+            }
+            ExportFormat::Resp => RecordWriter::Resp(writer),
+        };
+        Ok(writer)
+    }
+
+    /// 单个键读取失败时记录后继续, 连接断开时停止导出
+    fn write(
+        &mut self,
+        key: &str,
+        record: RedisResult<Option<KeyRecord>>,
+        report: &mut ExportReport,
+    ) -> Result<()> {
+        let record = match record {
+            Ok(Some(record)) => record,
+            Ok(None) => {
+                report.skipped += 1;
+                return Ok(());
+            }
+            Err(err) if err.is_io_error() || err.is_connection_dropped() => return Err(err.into()),
+            Err(err) => {
+                report.failed += 1;
+                if report.failures.len() < MAX_FAILURES {
+                    report.failures.push(KeyError {
+                        key: key.to_string(),
+                        message: err.to_string(),
+                    });
+                }
+                return Ok(());
+            }
+        };
+
+        match self {
+            RecordWriter::Json(writer) => {
+                serde_json::to_writer(&mut *writer, &record)?;
+                writer.write_all(b"\n")?;
+            }
+            RecordWriter::Csv(writer) => {
+                let ttl = record.pttl.to_string();
+                match record.value {
+                    KeyValue::String(ref value) => {
+                        writer.write_record([&record.key, "string", &ttl, "", value])?;
+                    }
+                    KeyValue::Hash(ref values) => {
+                        for (field, value) in values {
+                            writer.write_record([&record.key, "hash", &ttl, field, value])?;
+                        }
+                    }
+                    _ => {
+                        report.skipped += 1;
+                        return Ok(());
+                    }
+                }
+            }
+            RecordWriter::Resp(writer) => {
+                for cmd in record_cmds(&record) {
+                    writer.write_all(&cmd.get_packed_command())?;
+                }
+            }
+        }
+
+        report.exported += 1;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        match self {
+            RecordWriter::Json(writer) | RecordWriter::Resp(writer) => writer.flush()?,
+            RecordWriter::Csv(writer) => writer.flush()?,
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,225 @@
+use crate::{
+    error::Result,
+    model::{FcallRequest, FunctionInfo, FunctionLibrary, FunctionRestorePolicy, RespValue},
+    CmdLog, History, RedisState,
+};
+use redis::Value;
+use std::collections::HashMap;
+use tauri::State;
+use tracing::{info, instrument};
+
+use super::{query_all_masters, script::check_slot, select_db};
+
+/// 函数库列表, pattern为库名的匹配模式
+#[tauri::command]
+#[instrument(skip(state, history))]
+pub async fn function_list(
+    state: State<'_, RedisState>,
+    history: State<'_, History>,
+    id: String,
+    pattern: Option<String>,
+    with_code: Option<bool>,
+) -> Result<Vec<FunctionLibrary>> {
+    let mut redis_state = state.0.lock().await;
+    let (con, config) = redis_state.get_con_and_config(&id)?;
+
+    let mut cmd = redis::cmd("FUNCTION");
+    cmd.arg("LIST");
+    if let Some(pattern) = pattern.filter(|pattern| !pattern.is_empty()) {
+        cmd.arg("LIBRARYNAME").arg(pattern);
+    }
+    if with_code.unwrap_or_default() {
+        cmd.arg("WITHCODE");
+    }
+
+    let value: Value = cmd.log(history.0.clone(), config).query_async(con).await?;
+    let Value::Bulk(values) = value else {
+        return Err(format!("FUNCTION LIST返回值格式错误: {value:?}").into());
+    };
+
+    let libraries: Vec<_> = values.iter().map(parse_library).collect();
+
+    info!(count = libraries.len(), "获取函数库列表成功");
+    Ok(libraries)
+}
+
+/// 加载函数库, 返回库名; 集群模式下加载到所有主节点
+#[tauri::command]
+#[instrument(skip(state, history, code))]
+pub async fn function_load(
+    state: State<'_, RedisState>,
+    history: State<'_, History>,
+    id: String,
+    code: String,
+    replace: Option<bool>,
+) -> Result<String> {
+    let mut redis_state = state.0.lock().await;
+    let (con, config) = redis_state.get_con_and_config(&id)?;
+
+    let mut cmd = redis::cmd("FUNCTION");
+    cmd.arg("LOAD");
+    if replace.unwrap_or_default() {
+        cmd.arg("REPLACE");
+    }
+    cmd.arg(&code).log(history.0.clone(), config);
+
+    let library: String = query_all_masters(config, con, &cmd).await?;
+
+    info!(library, "加载函数库成功");
+    Ok(library)
+}
+
+/// 删除函数库; 集群模式下从所有主节点删除
+#[tauri::command]
+#[instrument(skip(state, history))]
+pub async fn function_delete(
+    state: State<'_, RedisState>,
+    history: State<'_, History>,
+    id: String,
+    library: String,
+) -> Result<()> {
+    let mut redis_state = state.0.lock().await;
+    let (con, config) = redis_state.get_con_and_config(&id)?;
+
+    let mut cmd = redis::cmd("FUNCTION");
+    cmd.arg("DELETE")
+        .arg(&library)
+        .log(history.0.clone(), config);
+    query_all_masters::<()>(config, con, &cmd).await?;
+
+    info!(library, "删除函数库成功");
+    Ok(())
+}
+
+/// 调用函数, 返回带类型的结果
+///
+/// 集群模式下命令先发送到函数名所在的节点, 再按MOVED重定向到KEYS所在的节点
+#[tauri::command]
+#[instrument(skip(state, history, request), fields(function = request.function))]
+pub async fn function_call(
+    state: State<'_, RedisState>,
+    history: State<'_, History>,
+    id: String,
+    db: u8,
+    request: FcallRequest,
+) -> Result<RespValue> {
+    let mut redis_state = state.0.lock().await;
+    let (con, config) = redis_state.get_con_and_config(&id)?;
+    select_db(config, db, con, &history).await?;
+
+    if config.cluster {
+        check_slot(&request.keys)?;
+    }
+
+    let name = if request.read_only {
+        "FCALL_RO"
+    } else {
+        "FCALL"
+    };
+    let res = redis::cmd(name)
+        .arg(&request.function)
+        .arg(request.keys.len())
+        .arg(&request.keys)
+        .arg(&request.args)
+        .log(history.0.clone(), config)
+        .query_async(con)
+        .await;
+
+    Ok(RespValue::from_result(res)?)
+}
+
+/// 使用FUNCTION DUMP将所有函数库保存到本地文件, 返回写入的字节数
+#[tauri::command]
+#[instrument(skip(state, history))]
+pub async fn function_dump(
+    state: State<'_, RedisState>,
+    history: State<'_, History>,
+    id: String,
+    path: String,
+) -> Result<u64> {
+    let payload: Vec<u8> = {
+        let mut redis_state = state.0.lock().await;
+        let (con, config) = redis_state.get_con_and_config(&id)?;
+        redis::cmd("FUNCTION")
+            .arg("DUMP")
+            .log(history.0.clone(), config)
+            .query_async(con)
+            .await?
+    };
+
+    std::fs::write(&path, &payload)?;
+
+    info!(path, bytes = payload.len(), "导出函数库成功");
+    Ok(payload.len() as u64)
+}
+
+/// 从本地文件恢复函数库, policy默认为APPEND; 集群模式下恢复到所有主节点
+#[tauri::command]
+#[instrument(skip(state, history))]
+pub async fn function_restore(
+    state: State<'_, RedisState>,
+    history: State<'_, History>,
+    id: String,
+    path: String,
+    policy: Option<FunctionRestorePolicy>,
+) -> Result<()> {
+    let payload = std::fs::read(&path)?;
+
+    let mut redis_state = state.0.lock().await;
+    let (con, config) = redis_state.get_con_and_config(&id)?;
+
+    let policy = match policy.unwrap_or(FunctionRestorePolicy::Append) {
+        FunctionRestorePolicy::Append => "APPEND",
+        FunctionRestorePolicy::Replace => "REPLACE",
+        FunctionRestorePolicy::Flush => "FLUSH",
+    };
+    let mut cmd = redis::cmd("FUNCTION");
+    cmd.arg("RESTORE").arg(&payload).arg(policy);
+    history.add_log(format!("function restore <{path}> {policy}"), config);
+
+    query_all_masters::<()>(config, con, &cmd).await?;
+
+    info!(path, "恢复函数库成功");
+    Ok(())
+}
+
+/// FUNCTION LIST中的一个库, 格式为交替出现的字段名和值
+fn parse_library(value: &Value) -> FunctionLibrary {
+    let fields = pairs(value);
+    FunctionLibrary {
+        name: string_field(&fields, "library_name").unwrap_or_default(),
+        engine: string_field(&fields, "engine").unwrap_or_default(),
+        functions: match fields.get("functions") {
+            Some(Value::Bulk(functions)) => functions.iter().map(parse_function).collect(),
+            _ => vec![],
+        },
+        code: string_field(&fields, "library_code"),
+    }
+}
+
+fn parse_function(value: &Value) -> FunctionInfo {
+    let fields = pairs(value);
+    FunctionInfo {
+        name: string_field(&fields, "name").unwrap_or_default(),
+        description: string_field(&fields, "description"),
+        flags: fields
+            .get("flags")
+            .and_then(|flags| redis::from_redis_value(flags).ok())
+            .unwrap_or_default(),
+    }
+}
+
+fn pairs(value: &Value) -> HashMap<String, &Value> {
+    let Value::Bulk(values) = value else {
+        return HashMap::new();
+    };
+    values
+        .chunks_exact(2)
+        .filter_map(|pair| Some((redis::from_redis_value(&pair[0]).ok()?, &pair[1])))
+        .collect()
+}
+
+/// 字段不存在或为nil时返回None
+fn string_field(fields: &HashMap<String, &Value>, name: &str) -> Option<String> {
+    redis::from_redis_value(fields.get(name)?).ok()
+}
@@ -0,0 +1,303 @@
+use crate::{
+    analysis::{HotKeyAggregator, DEFAULT_DELAY, DEFAULT_TOP},
+    cli_args::split_args,
+    config::RedisConfig,
+    error::Result,
+    job::{spawn_job, JobContext, Jobs},
+    model::{HotKeyMethod, HotKeyOptions, HotKeyProgress, HotKeyReport, HotKeySupport},
+    scan::{KeyScanner, SCAN_COUNT},
+    History, RedisState, SharedConnection,
+};
+use futures::{stream, StreamExt};
+use redis::{aio::ConnectionLike, RedisResult};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+use tauri::{State, Window};
+use tracing::{info, instrument};
+
+use super::get_cluster_clients;
+
+/// MONITOR默认采样时长(秒)
+const DEFAULT_DURATION: u64 = 10;
+/// MONITOR最长采样时长(秒), MONITOR会明显降低服务器的吞吐量
+const MAX_DURATION: u64 = 300;
+/// 等待MONITOR消息的最长时间, 超时后检查任务是否取消并更新进度
+const MONITOR_POLL: Duration = Duration::from_millis(200);
+
+/// 检查是否启用了LFU淘汰策略, 未启用时需要使用MONITOR采样
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn hot_keys_support(state: State<'_, RedisState>, id: String) -> Result<HotKeySupport> {
+    let config = state.0.lock().await.get_config(&id)?;
+    let mut con = SharedConnection::open(&config, 0).await?;
+
+    Ok(lfu_support(&mut con).await)
+}
+
+async fn lfu_support<C: ConnectionLike + Send>(con: &mut C) -> HotKeySupport {
+    let res: RedisResult<Vec<String>> = redis::cmd("CONFIG")
+        .arg("GET")
+        .arg("maxmemory-policy")
+        .query_async(con)
+        .await;
+
+    match res {
+        Ok(values) => {
+            let policy = values.get(1).cloned().unwrap_or_default();
+            let lfu = policy.ends_with("-lfu");
+            HotKeySupport {
+                message: (!lfu).then(|| {
+                    format!("maxmemory-policy为{policy}, OBJECT FREQ需要allkeys-lfu或volatile-lfu策略, 可以使用MONITOR采样")
+                }),
+                policy: Some(policy),
+                lfu,
+            }
+        }
+        Err(err) => HotKeySupport {
+            policy: None,
+            lfu: false,
+            message: Some(format!(
+                "无法读取maxmemory-policy: {err}, 可以使用MONITOR采样"
+            )),
+        },
+    }
+}
+
+/// 在后台查找热点键, 返回任务id
+#[tauri::command]
+#[instrument(skip(window, state, history, jobs))]
+pub async fn find_hot_keys(
+    window: Window,
+    state: State<'_, RedisState>,
+    history: State<'_, History>,
+    jobs: State<'_, Jobs>,
+    id: String,
+    db: u8,
+    options: HotKeyOptions,
+) -> Result<String> {
+    let config = state.0.lock().await.get_config(&id)?;
+    let mut con = SharedConnection::open(&config, db).await?;
+
+    if options.method == HotKeyMethod::Lfu {
+        let support = lfu_support(&mut con).await;
+        if let Some(message) = support.message {
+            return Err(message.into());
+        }
+    }
+
+    history.add_log(format!("hotkeys db{db} {:?}", options.method), &config);
+
+    let job_id = spawn_job(window, &jobs, "hotkeys", |ctx| {
+        hot_keys(ctx, config, con, db, options)
+    });
+
+    info!(job_id, "开始查找热点键");
+    Ok(job_id)
+}
+
+async fn hot_keys(
+    ctx: JobContext,
+    config: RedisConfig,
+    con: SharedConnection,
+    db: u8,
+    options: HotKeyOptions,
+) -> Result<HotKeyReport> {
+    let start = Instant::now();
+    let mut aggregator = HotKeyAggregator::new(
+        &config.split,
+        options.depth.unwrap_or(1),
+        options.top.unwrap_or(DEFAULT_TOP),
+    );
+
+    let scanned = match options.method {
+        HotKeyMethod::Lfu => scan_freq(&ctx, &config, con, &options, &mut aggregator).await?,
+        HotKeyMethod::Monitor => monitor(&ctx, &config, con, db, &options, &mut aggregator).await?,
+    };
+
+    let (keys, by_prefix) = aggregator.report();
+    info!(job_id = ctx.id(), scanned, "查找热点键完成");
+
+    Ok(HotKeyReport {
+        method: options.method,
+        scanned,
+        elapsed: start.elapsed().as_millis() as u64,
+        keys,
+        by_prefix,
+    })
+}
+
+/// 与redis-cli --hotkeys相同, 扫描键并查询OBJECT FREQ
+async fn scan_freq(
+    ctx: &JobContext,
+    config: &RedisConfig,
+    con: SharedConnection,
+    options: &HotKeyOptions,
+    aggregator: &mut HotKeyAggregator,
+) -> Result<u64> {
+    let start = Instant::now();
+    let delay = Duration::from_millis(options.delay.unwrap_or(DEFAULT_DELAY));
+    let pattern = options.pattern.as_deref().unwrap_or("*");
+    let scan_count = options.scan_count.unwrap_or(SCAN_COUNT).max(1);
+
+    let mut scanned = 0;
+    let mut scanner = KeyScanner::new(config, &con, pattern, scan_count).await?;
+    while let Some((mut node, keys)) = scanner.next_batch().await? {
+        if ctx.is_cancelled() {
+            break;
+        }
+
+        let mut pipe = redis::pipe();
+        for key in &keys {
+            pipe.cmd("OBJECT").arg("FREQ").arg(key);
+        }
+        // 扫描后被删除的键返回nil
+        let freqs: Vec<Option<u64>> = pipe.query_async(&mut node).await?;
+
+        scanned += keys.len() as u64;
+        for (key, freq) in keys.into_iter().zip(freqs) {
+            if let Some(freq) = freq {
+                aggregator.add(key, freq);
+            }
+        }
+
+        ctx.progress(&HotKeyProgress {
+            scanned,
+            elapsed: start.elapsed().as_millis() as u64,
+        });
+
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    Ok(scanned)
+}
+
+/// 在限定时间内通过MONITOR统计每个键被访问的次数, 集群模式下监听所有主节点
+async fn monitor(
+    ctx: &JobContext,
+    config: &RedisConfig,
+    mut con: SharedConnection,
+    db: u8,
+    options: &HotKeyOptions,
+    aggregator: &mut HotKeyAggregator,
+) -> Result<u64> {
+    let duration = Duration::from_secs(
+        options
+            .duration
+            .unwrap_or(DEFAULT_DURATION)
+            .clamp(1, MAX_DURATION),
+    );
+
+    let clients = if config.cluster {
+        get_cluster_clients(config, &mut con).await?
+    } else {
+        vec![redis::Client::open(config.clone())?]
+    };
+
+    let mut streams = vec![];
+    for client in clients {
+        let mut monitor = client.get_async_connection().await?.into_monitor();
+        monitor.monitor().await?;
+        streams.push(Box::pin(monitor.into_on_message::<String>()));
+    }
+    let mut messages = stream::select_all(streams);
+
+    let start = Instant::now();
+    let mut commands = 0;
+    let mut counts: HashMap<String, u64> = HashMap::new();
+    while !ctx.is_cancelled() {
+        let Some(remaining) = duration.checked_sub(start.elapsed()) else {
+            break;
+        };
+
+        match tokio::time::timeout(remaining.min(MONITOR_POLL), messages.next()).await {
+            Ok(Some(line)) => {
+                let Some((line_db, args)) = parse_monitor_line(&line) else {
+                    continue;
+                };
+                if line_db != db as i64 {
+                    continue;
+                }
+
+                commands += 1;
+                for key in command_keys(&args) {
+                    *counts.entry(key).or_default() += 1;
+                }
+            }
+            // 所有连接都已断开
+            Ok(None) => break,
+            Err(_) => {}
+        }
+
+        ctx.progress(&HotKeyProgress {
+            scanned: commands,
+            elapsed: start.elapsed().as_millis() as u64,
+        });
+    }
+
+    for (key, hits) in counts {
+        aggregator.add(key, hits);
+    }
+
+    Ok(commands)
+}
+
+/// 解析MONITOR的输出, 例如`1339518083.107412 [0 127.0.0.1:60866] "get" "foo"`, 返回db和命令参数
+fn parse_monitor_line(line: &str) -> Option<(i64, Vec<Vec<u8>>)> {
+    let start = line.find('[')?;
+    let end = start + line[start..].find("] ")?;
+    let db = line[start + 1..end].split(' ').next()?.parse().ok()?;
+    let args = split_args(&line[end + 2..]).ok()?;
+
+    Some((db, args))
+}
+
+/// 按命令的参数规则取出访问的键, 未知命令视为第一个参数是键
+fn command_keys(args: &[Vec<u8>]) -> Vec<String> {
+    let Some((name, args)) = args.split_first() else {
+        return vec![];
+    };
+
+    let keys: Vec<&Vec<u8>> = match String::from_utf8_lossy(name).to_lowercase().as_str() {
+        "del" | "unlink" | "exists" | "touch" | "mget" | "watch" | "sinter" | "sunion"
+        | "sdiff" | "sinterstore" | "sunionstore" | "sdiffstore" | "pfcount" | "pfmerge"
+        | "rename" | "renamenx" => args.iter().collect(),
+        "mset" | "msetnx" => args.iter().step_by(2).collect(),
+        "blpop" | "brpop" | "bzpopmin" | "bzpopmax" => {
+            args.iter().take(args.len().saturating_sub(1)).collect()
+        }
+        "eval" | "evalsha" | "eval_ro" | "evalsha_ro" | "fcall" | "fcall_ro" => {
+            let numkeys = args
+                .get(1)
+                .and_then(|n| String::from_utf8_lossy(n).parse().ok())
+                .unwrap_or(0);
+            args.iter().skip(2).take(numkeys).collect()
+        }
+        "xread" | "xreadgroup" => {
+            let streams = args
+                .iter()
+                .position(|arg| arg.eq_ignore_ascii_case(b"streams"))
+                .map(|pos| &args[pos + 1..])
+                .unwrap_or_default();
+            streams.iter().take(streams.len() / 2).collect()
+        }
+        // 子命令之后是键, 例如OBJECT FREQ key, MEMORY USAGE key
+        "object" | "memory" | "xinfo" => args.get(1).into_iter().collect(),
+        "ping" | "echo" | "select" | "info" | "config" | "client" | "auth" | "hello"
+        | "monitor" | "multi" | "exec" | "discard" | "dbsize" | "flushdb" | "flushall" | "keys"
+        | "scan" | "time" | "command" | "slowlog" | "cluster" | "script" | "function"
+        | "publish" | "spublish" | "subscribe" | "psubscribe" | "ssubscribe" | "unsubscribe"
+        | "punsubscribe" | "sunsubscribe" | "latency" | "debug" | "save" | "bgsave"
+        | "bgrewriteaof" | "lastsave" | "role" | "replicaof" | "slaveof" | "wait" | "randomkey"
+        | "readonly" | "readwrite" | "quit" | "reset" | "acl" | "module" | "swapdb"
+        | "shutdown" | "sync" | "psync" | "replconf" => vec![],
+        _ => args.first().into_iter().collect(),
+    };
+
+    keys.into_iter()
+        .map(|key| String::from_utf8_lossy(key).into_owned())
+        .collect()
+}
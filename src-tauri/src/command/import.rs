@@ -0,0 +1,369 @@
+use crate::{
+    cli_args::split_args,
+    error::Result,
+    job::{spawn_job, JobContext, Jobs},
+    model::{
+        CsvColumn, CsvMapping, ImportFormat, ImportOptions, ImportProgress, KeyRecord, LineError,
+    },
+    value::record_cmds,
+    History, RedisState, SharedConnection,
+};
+use anyhow::Context;
+use redis::Cmd;
+use tauri::{State, Window};
+use tracing::{info, instrument};
+
+/// 默认每批执行的记录数量
+const DEFAULT_BATCH_SIZE: usize = 500;
+/// 最多保留的错误行
+const MAX_ERRORS: usize = 1000;
+
+/// 一条记录及其对应的命令
+struct ImportItem {
+    line: usize,
+    cmds: Vec<Cmd>,
+}
+
+/// 从本地文件导入数据, 返回任务id
+#[tauri::command]
+#[instrument(skip(window, state, history, jobs))]
+pub async fn import_keys(
+    window: Window,
+    state: State<'_, RedisState>,
+    history: State<'_, History>,
+    jobs: State<'_, Jobs>,
+    id: String,
+    db: u8,
+    options: ImportOptions,
+) -> Result<String> {
+    let config = state.0.lock().await.get_config(&id)?;
+    let con = SharedConnection::open(&config, db).await?;
+
+    if !options.dry_run {
+        history.add_log(format!("import db{db} <= {}", options.path), &config);
+    }
+
+    let job_id = spawn_job(window, &jobs, "import", |ctx| import(ctx, con, options));
+
+    info!(job_id, "开始导入");
+    Ok(job_id)
+}
+
+async fn import(
+    ctx: JobContext,
+    con: SharedConnection,
+    options: ImportOptions,
+) -> Result<ImportProgress> {
+    let data = std::fs::read(&options.path).context(format!("读取文件失败: {}", options.path))?;
+
+    let mut progress = ImportProgress {
+        dry_run: options.dry_run,
+        ..Default::default()
+    };
+    let items = match options.format {
+        ImportFormat::Json => parse_json(&data, &mut progress),
+        ImportFormat::Csv => {
+            let mapping = options.csv.clone().unwrap_or_default();
+            parse_csv(&data, &mapping, &mut progress)?
+        }
+        ImportFormat::Resp => parse_resp(&data, &mut progress),
+        ImportFormat::Cli => parse_cli(&data, &mut progress),
+    };
+
+    progress.total = items.len() as u64;
+    progress.commands = items.iter().map(|item| item.cmds.len() as u64).sum();
+    if options.dry_run {
+        return Ok(progress);
+    }
+
+    let cluster = matches!(con, SharedConnection::ClusterConnection(_));
+    let batch_size = options.batch_size.unwrap_or(DEFAULT_BATCH_SIZE).max(1);
+    for batch in items.chunks(batch_size) {
+        if ctx.is_cancelled() {
+            return Ok(progress);
+        }
+
+        // 不同记录之间并发执行, 同一条记录的命令在一个pipeline中按顺序执行
+        let requests = batch.iter().map(|item| {
+            let mut con = con.clone();
+            async move {
+                item_pipeline(item, cluster)
+                    .query_async::<_, ()>(&mut con)
+                    .await
+            }
+        });
+        let results = futures::future::join_all(requests).await;
+
+        // 按记录统计, 一条记录的任意命令失败时记为失败
+        for (item, res) in batch.iter().zip(results) {
+            match res {
+                Ok(()) => progress.succeeded += 1,
+                Err(err) => {
+                    progress.failed += 1;
+                    push_error(&mut progress, item.line, err.to_string());
+                }
+            }
+        }
+        progress.processed += batch.len() as u64;
+
+        ctx.progress(&progress);
+    }
+
+    info!(job_id = ctx.id(), ?progress, "导入完成");
+    Ok(progress)
+}
+
+/// 一条记录的命令作为一个pipeline, 有多条命令时在单机模式下使用事务
+///
+/// 集群模式下的事务没有键, 无法路由到对应节点; 一条记录的命令属于同一个键, 按顺序执行即可
+fn item_pipeline(item: &ImportItem, cluster: bool) -> redis::Pipeline {
+    let mut pipe = redis::pipe();
+    if !cluster && item.cmds.len() > 1 {
+        pipe.atomic();
+    }
+    for cmd in &item.cmds {
+        pipe.add_command(cmd.clone()).ignore();
+    }
+    pipe
+}
+
+fn push_error(progress: &mut ImportProgress, line: usize, message: String) {
+    if progress.errors.len() < MAX_ERRORS {
+        progress.errors.push(LineError { line, message });
+    }
+}
+
+/// 每行一个导出的json对象
+fn parse_json(data: &[u8], progress: &mut ImportProgress) -> Vec<ImportItem> {
+    let mut items = vec![];
+    for (index, line) in String::from_utf8_lossy(data).lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match serde_json::from_str::<KeyRecord>(line) {
+            Ok(record) => items.push(ImportItem {
+                line: index + 1,
+                cmds: record_cmds(&record),
+            }),
+            Err(err) => push_error(progress, index + 1, err.to_string()),
+        }
+    }
+    items
+}
+
+/// 按列映射读取csv, field列不为空时写入哈希, 否则写入字符串
+fn parse_csv(
+    data: &[u8],
+    mapping: &CsvMapping,
+    progress: &mut ImportProgress,
+) -> Result<Vec<ImportItem>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(mapping.has_headers)
+        .delimiter(mapping.delimiter.unwrap_or(',') as u8)
+        .flexible(true)
+        .from_reader(data);
+
+    let headers = if mapping.has_headers {
+        Some(reader.headers()?.clone())
+    } else {
+        None
+    };
+    let column = |column: &CsvColumn| -> Result<usize> {
+        match column {
+            CsvColumn::Index(index) => Ok(*index),
+            CsvColumn::Name(name) => headers
+                .as_ref()
+                .and_then(|headers| headers.iter().position(|header| header == name))
+                .ok_or_else(|| format!("csv中不存在列: {name}").into()),
+        }
+    };
+    let key_column = column(&mapping.key_column)?;
+    let value_column = column(&mapping.value_column)?;
+    let field_column = mapping.field_column.as_ref().map(column).transpose()?;
+    let ttl_column = mapping.ttl_column.as_ref().map(column).transpose()?;
+
+    let mut items = vec![];
+    for record in reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(err) => {
+                let line = err
+                    .position()
+                    .map(|p| p.line() as usize)
+                    .unwrap_or_default();
+                push_error(progress, line, err.to_string());
+                continue;
+            }
+        };
+        let line = record
+            .position()
+            .map(|p| p.line() as usize)
+            .unwrap_or_default();
+
+        let (Some(key), Some(value)) = (record.get(key_column), record.get(value_column)) else {
+            push_error(progress, line, "缺少key或value列".to_string());
+            continue;
+        };
+        if key.is_empty() {
+            push_error(progress, line, "key不能为空".to_string());
+            continue;
+        }
+
+        let ttl = match ttl_column.and_then(|column| record.get(column)) {
+            Some(ttl) if !ttl.is_empty() => match ttl.parse::<i64>() {
+                Ok(ttl) => ttl,
+                Err(_) => {
+                    push_error(progress, line, format!("无效的过期时间: {ttl}"));
+                    continue;
+                }
+            },
+            _ => -1,
+        };
+
+        let mut cmds = vec![];
+        match field_column.and_then(|column| record.get(column)) {
+            Some(field) if !field.is_empty() => {
+                let mut cmd = redis::cmd("HSET");
+                cmd.arg(key).arg(field).arg(value);
+                cmds.push(cmd);
+            }
+            _ => {
+                let mut cmd = redis::cmd("SET");
+                cmd.arg(key).arg(value);
+                cmds.push(cmd);
+            }
+        }
+        if ttl > 0 {
+            let mut cmd = redis::cmd("PEXPIRE");
+            cmd.arg(key).arg(ttl);
+            cmds.push(cmd);
+        }
+
+        items.push(ImportItem { line, cmds });
+    }
+
+    Ok(items)
+}
+
+/// 每行一条redis-cli命令, 忽略空行和#开头的注释
+fn parse_cli(data: &[u8], progress: &mut ImportProgress) -> Vec<ImportItem> {
+    let mut items = vec![];
+    for (index, line) in String::from_utf8_lossy(data).lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        match split_args(line) {
+            Ok(args) => items.push(ImportItem {
+                line: index + 1,
+                cmds: vec![args_cmd(args)],
+            }),
+            Err(err) => push_error(progress, index + 1, err),
+        }
+    }
+    items
+}
+
+/// RESP数组格式的命令, 格式错误时无法继续定位后续命令, 直接停止解析
+fn parse_resp(data: &[u8], progress: &mut ImportProgress) -> Vec<ImportItem> {
+    let mut items = vec![];
+    let mut reader = RespReader {
+        data,
+        pos: 0,
+        line: 1,
+    };
+
+    while reader.skip_blank() {
+        let line = reader.line;
+        match reader.read_command() {
+            Ok(args) => items.push(ImportItem {
+                line,
+                cmds: vec![args_cmd(args)],
+            }),
+            Err(err) => {
+                push_error(progress, reader.line, err);
+                break;
+            }
+        }
+    }
+    items
+}
+
+fn args_cmd(args: Vec<Vec<u8>>) -> Cmd {
+    let mut cmd = Cmd::new();
+    for arg in args {
+        cmd.arg(arg);
+    }
+    cmd
+}
+
+struct RespReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    line: usize,
+}
+
+impl<'a> RespReader<'a> {
+    /// 跳过命令之间的空白, 还有数据时返回true
+    fn skip_blank(&mut self) -> bool {
+        while let Some(&c) = self.data.get(self.pos) {
+            if !c.is_ascii_whitespace() {
+                return true;
+            }
+            if c == b'\n' {
+                self.line += 1;
+            }
+            self.pos += 1;
+        }
+        false
+    }
+
+    fn read_command(&mut self) -> Result<Vec<Vec<u8>>, String> {
+        let count = self.read_header(b'*')?;
+        // 长度来自文件, 不能直接用于预分配
+        let mut args = Vec::with_capacity(count.min(1024));
+        for _ in 0..count {
+            let len = self.read_header(b'$')?;
+            let remaining = self.data.len() - self.pos;
+            if len > remaining.saturating_sub(2) {
+                return Err("bulk字符串长度超出文件末尾".to_string());
+            }
+            let end = self.pos + len;
+            if &self.data[end..end + 2] != b"\r\n" {
+                return Err("bulk字符串长度不正确".to_string());
+            }
+            let arg = &self.data[self.pos..end];
+            self.line += arg.iter().filter(|c| **c == b'\n').count() + 1;
+            args.push(arg.to_vec());
+            self.pos = end + 2;
+        }
+
+        if args.is_empty() {
+            return Err("命令不能为空".to_string());
+        }
+        Ok(args)
+    }
+
+    /// 读取`*N\r\n`或`$N\r\n`中的N
+    fn read_header(&mut self, prefix: u8) -> Result<usize, String> {
+        if self.data.get(self.pos) != Some(&prefix) {
+            return Err(format!("应为'{}'", prefix as char));
+        }
+
+        let rest = &self.data[self.pos + 1..];
+        let end = rest
+            .windows(2)
+            .position(|w| w == b"\r\n")
+            .ok_or_else(|| "缺少\\r\\n".to_string())?;
+        let len = std::str::from_utf8(&rest[..end])
+            .ok()
+            .and_then(|len| len.parse().ok())
+            .ok_or_else(|| "无效的长度".to_string())?;
+
+        self.pos += end + 3;
+        self.line += 1;
+        Ok(len)
+    }
+}
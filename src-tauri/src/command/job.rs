@@ -0,0 +1,13 @@
+use crate::{error::Result, job::Jobs};
+use tauri::State;
+use tracing::{info, instrument};
+
+/// 取消后台任务
+#[tauri::command]
+#[instrument(skip(jobs))]
+pub async fn cancel_job(jobs: State<'_, Jobs>, job_id: String) -> Result<bool> {
+    let cancelled = jobs.cancel(&job_id);
+
+    info!(cancelled, "取消任务");
+    Ok(cancelled)
+}
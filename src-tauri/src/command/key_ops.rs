@@ -1,23 +1,46 @@
-use super::state::RedisState;
-use crate::{error::Result, get_cluster_clients, model::*, select_db, CmdLog, History, LogArgs};
+use super::{
+    delete::delete_keys,
+    state::{OfflineState, RedisState},
+};
+use crate::{
+    config::RedisConfig,
+    dump::dump_key,
+    error::Result,
+    get_cluster_clients, is_unsupported_error,
+    model::*,
+    select_db,
+    snapshot::SnapshotStore,
+    undo::{UndoEntry, UndoState},
+    value::len_cmd,
+    CmdLog, History, LogArgs, RedisConnection, SharedConnection,
+};
 use anyhow::Context;
-use redis::{AsyncCommands, AsyncIter};
+use chrono::Local;
+use redis::{AsyncCommands, AsyncIter, Cmd, ErrorKind, FromRedisValue, Value};
 
 use serde_json::json;
 use std::collections::HashMap;
 use tauri::State;
-use tracing::{info, instrument};
+use tracing::{info, instrument, warn};
+
+/// 有序集合范围查询默认每页的数量
+const ZRANGE_PAGE_SIZE: usize = 100;
 
 /// 获取键的类型
 #[tauri::command]
-#[instrument(skip(state, history))]
+#[instrument(skip(state, offline, history))]
 pub async fn get_key_type(
     state: State<'_, RedisState>,
+    offline: State<'_, OfflineState>,
     history: State<'_, History>,
     id: String,
     db: u8,
     key: String,
 ) -> Result<String> {
+    if let Some(instance) = offline.get(&id) {
+        return Ok(instance.key_type(db, &key));
+    }
+
     let mut redis_state = state.0.lock().await;
     let (con, config) = redis_state.get_con_and_config(&id)?;
     select_db(config, db, con, &history).await?;
@@ -31,11 +54,12 @@ pub async fn get_key_type(
 }
 
 /// 删除一个键
-#[instrument(skip(state, history))]
+#[instrument(skip(state, history, undo))]
 #[tauri::command]
 pub async fn del_key(
     state: State<'_, RedisState>,
     history: State<'_, History>,
+    undo: State<'_, UndoState>,
     id: String,
     db: u8,
     key: String,
@@ -44,6 +68,9 @@ pub async fn del_key(
     let (con, config) = redis_state.get_con_and_config(&id)?;
     select_db(config, db, con, &history).await?;
 
+    let entry = undo_entry(con, config, &history, db, "del", &key).await;
+    undo.push(&id, entry);
+
     con.del(&key)
         .await
         .context(format!("删除键失败, id: {id}, key: {key}"))?;
@@ -54,71 +81,46 @@ pub async fn del_key(
 }
 
 /// 删除命名空间匹配的键
-#[instrument(skip(state, history))]
+///
+/// 分批使用UNLINK删除, 集群模式下按槽分组, 大量的键建议使用delete_match_keys在后台删除
+#[instrument(skip(state, history, undo))]
 #[tauri::command]
 pub async fn del_match_keys(
     state: State<'_, RedisState>,
     history: State<'_, History>,
+    undo: State<'_, UndoState>,
     id: String,
     db: u8,
     match_key: String,
 ) -> Result<()> {
-    let mut redis_state = state.0.lock().await;
-    let (con, config) = redis_state.get_con_and_config(&id)?;
-    select_db(config, db, con, &history).await?;
-
-    if config.cluster {
-        let clients = get_cluster_clients(config, con).await?;
-
-        let mut keys_log = vec![];
-        for client in clients {
-            let mut keys = vec![];
-            let mut cluster_con = client.get_async_connection().await?;
-
-            {
-                let mut iter: AsyncIter<'_, String> = cluster_con.scan_match(&match_key).await?;
-                while let Some(key) = iter.next_item().await {
-                    keys.push(key);
-                }
-                info!(?keys, "当前要删除的keys:");
-            }
-
-            if !keys.is_empty() {
-                con.del(&keys).await?;
-                keys_log.extend(keys);
-            }
-        }
-
-        let mut logs = LogArgs!["del"];
-        logs.extend(keys_log);
-        history.add_log_vec(logs, config);
-    } else {
-        let mut keys = vec![];
-        {
-            let mut iter: AsyncIter<'_, String> = con.scan_match(&match_key).await?;
-            while let Some(key) = iter.next_item().await {
-                keys.push(key);
-            }
-        }
-
-        if !keys.is_empty() {
-            con.del(&keys).await?;
-        }
-        let mut logs = LogArgs!["del"];
-        logs.extend(keys);
-        history.add_log_vec(logs, config);
-    }
+    let config = state.0.lock().await.get_config(&id)?;
+    let con = SharedConnection::open(&config, db).await?;
+
+    let options = DeleteOptions {
+        pattern: match_key,
+        batch_size: None,
+        delay: Some(0),
+        max_keys: None,
+    };
+    let mut entry = UndoEntry::new("unlink", db);
+    let res = delete_keys(None, &config, con, &options, Some(&mut entry)).await;
+    undo.push(&id, entry);
+    let progress = res?;
+    history.add_log(format!("[db{db}] unlink {}", options.pattern), &config);
 
-    info!("删除多个key成功");
+    info!(?progress, "删除多个key成功");
     Ok(())
 }
 
 /// 删除指定key类型中的部分内容
 #[tauri::command]
-#[instrument(skip(state, history))]
+#[instrument(skip(state, history, undo, snapshots))]
+#[allow(clippy::too_many_arguments)]
 pub async fn del_key_by_value(
     state: State<'_, RedisState>,
     history: State<'_, History>,
+    undo: State<'_, UndoState>,
+    snapshots: State<'_, SnapshotStore>,
     id: String,
     db: u8,
     key: String,
@@ -131,6 +133,10 @@ pub async fn del_key_by_value(
     let (con, config) = redis_state.get_con_and_config(&id)?;
     select_db(config, db, con, &history).await?;
 
+    let entry = undo_entry(con, config, &history, db, "del_value", &key).await;
+    undo.push(&id, entry);
+    snapshots.capture(con, &id, db, "del_value", &key).await;
+
     let typ: String = redis::cmd("TYPE")
         .arg(&key)
         .log(history.0.clone(), config)
@@ -207,13 +213,18 @@ pub async fn clear_keys(
 
 /// 获取指定数据库中的所有键
 #[tauri::command]
-#[instrument(skip(state, history))]
+#[instrument(skip(state, offline, history))]
 pub async fn get_keys_by_db(
     state: State<'_, RedisState>,
+    offline: State<'_, OfflineState>,
     history: State<'_, History>,
     id: String,
     db: u8,
 ) -> Result<Vec<String>> {
+    if let Some(instance) = offline.get(&id) {
+        return Ok(instance.keys(db));
+    }
+
     let mut redis_state = state.0.lock().await;
     let (con, config) = redis_state.get_con_and_config(&id)?;
 
@@ -249,56 +260,182 @@ pub async fn get_keys_by_db(
 
 /// 获取键的基础信息
 #[tauri::command]
-#[instrument(skip(state, history))]
+#[instrument(skip(state, offline, history))]
 pub async fn get_key_info(
     state: State<'_, RedisState>,
+    offline: State<'_, OfflineState>,
     history: State<'_, History>,
     id: String,
     db: u8,
     key: String,
 ) -> Result<KeyInfo> {
+    if let Some(instance) = offline.get(&id) {
+        return instance.key_info(db, &key);
+    }
+
     let mut redis_state = state.0.lock().await;
     let (con, config) = redis_state.get_con_and_config(&id)?;
     select_db(config, db, con, &history).await?;
 
-    let (typ, ttl): (String, i64) = redis::pipe()
+    let (typ, ttl, pttl): (String, i64, i64) = redis::pipe()
         .key_type(&key)
         .ttl(&key)
+        .pttl(&key)
         .log(history.0.clone(), config)
         .query_async(con)
         .await?;
 
     let label = typ[0..1].to_uppercase() + &typ[1..];
 
-    let keyinfo = KeyInfo {
+    let mut keyinfo = KeyInfo {
         key,
         r#type: typ,
         label,
         ttl,
+        pttl,
+        expire_at: expire_at(pttl),
+        encoding: None,
+        memory: None,
+        idle_time: None,
+        freq: None,
+        refcount: None,
+        elements: None,
     };
 
+    // 键不存在时没有元信息
+    if keyinfo.r#type != "none" {
+        let (lfu, values) = query_meta(con, config, &history, &keyinfo.r#type, &keyinfo.key).await;
+
+        keyinfo.encoding = meta_value(&values, 0);
+        keyinfo.refcount = meta_value(&values, 1);
+        keyinfo.memory = meta_value(&values, 2);
+        if lfu {
+            keyinfo.freq = meta_value(&values, 3);
+        } else {
+            keyinfo.idle_time = meta_value(&values, 3);
+        }
+        keyinfo.elements = meta_value(&values, 4);
+    }
+
     info!(?keyinfo, "获取key基础信息成功");
 
     Ok(keyinfo)
 }
 
+/// 元信息命令, 依次为ENCODING, REFCOUNT, MEMORY USAGE, IDLETIME或FREQ, 元素数量
+///
+/// 元素数量的命令取决于类型, 所以需要先获取类型
+fn meta_cmds(key_type: &str, key: &str, lfu: bool) -> Vec<Cmd> {
+    let object = |sub: &str| {
+        let mut cmd = redis::cmd("OBJECT");
+        cmd.arg(sub).arg(key);
+        cmd
+    };
+    let mut memory = redis::cmd("MEMORY");
+    memory.arg("USAGE").arg(key);
+
+    // IDLETIME和FREQ只有一个可用, 取决于maxmemory-policy
+    let access = object(if lfu { "FREQ" } else { "IDLETIME" });
+    let mut cmds = vec![object("ENCODING"), object("REFCOUNT"), memory, access];
+    cmds.extend(len_cmd(key_type, key));
+    cmds
+}
+
+/// 在一个pipeline中查询元信息, 返回是否使用了FREQ以及每条命令的结果
+///
+/// pipeline中任意命令出错时整体返回错误: LFU策略下改用FREQ重试,
+/// 其他情况(命令被禁用或重命名)逐条查询, 失败的命令返回nil且不记录到历史
+async fn query_meta(
+    con: &mut RedisConnection,
+    config: &RedisConfig,
+    history: &History,
+    key_type: &str,
+    key: &str,
+) -> (bool, Vec<Value>) {
+    let mut lfu = false;
+    let cmds = loop {
+        let cmds = meta_cmds(key_type, key, lfu);
+        let mut pipe = redis::pipe();
+        for cmd in &cmds {
+            pipe.add_command(cmd.clone());
+        }
+
+        match pipe.query_async::<_, Vec<Value>>(con).await {
+            Ok(values) => {
+                pipe.log(history.0.clone(), config);
+                return (lfu, values);
+            }
+            Err(err) if !lfu && err.detail().is_some_and(|detail| detail.contains("LFU")) => {
+                lfu = true;
+            }
+            Err(err) => {
+                warn!(?err, "批量获取key元信息失败");
+                break cmds;
+            }
+        }
+    };
+
+    let mut values = Vec::with_capacity(cmds.len());
+    for cmd in cmds {
+        match cmd.query_async::<_, Value>(con).await {
+            Ok(value) => {
+                cmd.log(history.0.clone(), config);
+                values.push(value);
+            }
+            Err(err) => {
+                warn!(?err, "获取key元信息失败");
+                values.push(Value::Nil);
+            }
+        }
+    }
+    (lfu, values)
+}
+
+/// 命令失败或不适用时返回None
+fn meta_value<T: FromRedisValue>(values: &[Value], index: usize) -> Option<T> {
+    redis::from_redis_value::<Option<T>>(values.get(index)?)
+        .ok()
+        .flatten()
+}
+
 /// 获取键对应的详细信息
 #[tauri::command]
-#[instrument(skip(state, history))]
+#[instrument(skip(state, offline, history))]
 pub async fn get_key_detail(
     state: State<'_, RedisState>,
+    offline: State<'_, OfflineState>,
     history: State<'_, History>,
     id: String,
     db: u8,
     key: String,
+    search: Option<String>,
 ) -> Result<KeyContentDetail> {
+    if let Some(instance) = offline.get(&id) {
+        return instance.key_detail(db, &key, search.as_deref());
+    }
+
     let mut redis_state = state.0.lock().await;
     let (con, config) = redis_state.get_con_and_config(&id)?;
     select_db(config, db, con, &history).await?;
 
-    let (typ, ttl): (String, i64) = redis::pipe()
+    load_key_detail(con, config, &history, key, search.as_deref()).await
+}
+
+/// 在已选择数据库的连接上读取键的详细信息
+pub(crate) async fn load_key_detail(
+    con: &mut RedisConnection,
+    config: &RedisConfig,
+    history: &History,
+    key: String,
+    search: Option<&str>,
+) -> Result<KeyContentDetail> {
+    // 哈希, 集合和有序集合的MATCH模式, 由服务端过滤
+    let pattern = search.filter(|search| !search.is_empty()).unwrap_or("*");
+
+    let (typ, ttl, pttl): (String, i64, i64) = redis::pipe()
         .key_type(&key)
         .ttl(&key)
+        .pttl(&key)
         .log(history.0.clone(), config)
         .query_async(con)
         .await?;
@@ -309,6 +446,8 @@ pub async fn get_key_detail(
         label: typ[0..1].to_uppercase() + &typ[1..],
         size: 0,
         ttl,
+        pttl,
+        expire_at: expire_at(pttl),
         value: RedisValue::String("".into()),
     };
 
@@ -331,7 +470,7 @@ pub async fn get_key_detail(
         }
         "set" => {
             let count: usize = con.scard(&key).await?;
-            let mut iter: AsyncIter<'_, String> = con.sscan_match(&key, "*").await?;
+            let mut iter: AsyncIter<'_, String> = con.sscan_match(&key, pattern).await?;
             let mut values = vec![];
             while let Some(val) = iter.next_item().await {
                 values.push(val);
@@ -340,7 +479,21 @@ pub async fn get_key_detail(
             keyinfo.value = RedisValue::Set(values);
 
             history.add_log_vec(LogArgs!["scard", &key], config);
-            history.add_log_vec(LogArgs!["sscan", &key, "MATCH", "*"], config);
+            history.add_log_vec(LogArgs!["sscan", &key, "MATCH", pattern], config);
+        }
+        "zset" if pattern != "*" => {
+            let count: usize = con.zcard(&key).await?;
+            let mut iter: AsyncIter<'_, (String, f64)> = con.zscan_match(&key, pattern).await?;
+            let mut values = vec![];
+            while let Some((member, score)) = iter.next_item().await {
+                values.push(Z::new(score, member));
+            }
+
+            keyinfo.size = count;
+            keyinfo.value = RedisValue::ZSet(values);
+
+            history.add_log_vec(LogArgs!["zcard", &key], config);
+            history.add_log_vec(LogArgs!["zscan", &key, "MATCH", pattern], config);
         }
         "zset" => {
             let count: usize = con.zcard(&key).await?;
@@ -358,7 +511,7 @@ pub async fn get_key_detail(
         }
         "hash" => {
             let count: usize = con.hlen(&key).await?;
-            let mut iter: AsyncIter<'_, (String, String)> = con.hscan_match(&key, "*").await?;
+            let mut iter: AsyncIter<'_, (String, String)> = con.hscan_match(&key, pattern).await?;
             let mut values = vec![];
             while let Some((key, value)) = iter.next_item().await {
                 values.push(HashResult::new(key, value));
@@ -368,7 +521,7 @@ pub async fn get_key_detail(
             keyinfo.value = RedisValue::Hash(values);
 
             history.add_log_vec(LogArgs!["hlen", &key], config);
-            history.add_log_vec(LogArgs!["hscan", &key, "MATCH", "*"], config);
+            history.add_log_vec(LogArgs!["hscan", &key, "MATCH", pattern], config);
         }
         "stream" => {
             let count: usize = con.xlen(&key).await?;
@@ -406,12 +559,89 @@ pub async fn get_key_detail(
     Ok(keyinfo)
 }
 
-/// 重命名键
+/// 按分数或字典序范围分页查询有序集合
 #[tauri::command]
 #[instrument(skip(state, history))]
+pub async fn get_zset_range(
+    state: State<'_, RedisState>,
+    history: State<'_, History>,
+    id: String,
+    db: u8,
+    key: String,
+    query: ZRangeQuery,
+) -> Result<ZRangePage> {
+    let mut redis_state = state.0.lock().await;
+    let (con, config) = redis_state.get_con_and_config(&id)?;
+    select_db(config, db, con, &history).await?;
+
+    let offset = query.offset.unwrap_or(0);
+    let count = query.count.unwrap_or(ZRANGE_PAGE_SIZE).max(1);
+    let (range_cmd, count_cmd, min, max) = match query.by {
+        ZRangeBy::Score { ref min, ref max } if query.rev => {
+            ("ZREVRANGEBYSCORE", "ZCOUNT", max, min)
+        }
+        ZRangeBy::Score { ref min, ref max } => ("ZRANGEBYSCORE", "ZCOUNT", min, max),
+        ZRangeBy::Lex { ref min, ref max } if query.rev => {
+            ("ZREVRANGEBYLEX", "ZLEXCOUNT", max, min)
+        }
+        ZRangeBy::Lex { ref min, ref max } => ("ZRANGEBYLEX", "ZLEXCOUNT", min, max),
+    };
+    let by_score = matches!(query.by, ZRangeBy::Score { .. });
+
+    // ZCOUNT和ZLEXCOUNT的参数总是min max
+    let (count_min, count_max) = if query.rev { (max, min) } else { (min, max) };
+    let total: u64 = redis::cmd(count_cmd)
+        .arg(&key)
+        .arg(count_min)
+        .arg(count_max)
+        .log(history.0.clone(), config)
+        .query_async(con)
+        .await?;
+
+    // 多取一个用于判断是否还有下一页
+    let mut cmd = redis::cmd(range_cmd);
+    cmd.arg(&key).arg(min).arg(max);
+    if by_score {
+        cmd.arg("WITHSCORES");
+    }
+    cmd.arg("LIMIT").arg(offset).arg(count + 1);
+
+    let mut values: Vec<Z> = if by_score {
+        let data: Vec<(String, f64)> = cmd.log(history.0.clone(), config).query_async(con).await?;
+        data.into_iter().map(|d| Z::new(d.1, d.0)).collect()
+    } else {
+        let members: Vec<String> = cmd.log(history.0.clone(), config).query_async(con).await?;
+        let mut pipe = redis::pipe();
+        for member in &members {
+            pipe.zscore(&key, member);
+        }
+        let scores: Vec<Option<f64>> = pipe.log(history.0.clone(), config).query_async(con).await?;
+        members
+            .into_iter()
+            .zip(scores)
+            .map(|(member, score)| Z::new(score.unwrap_or_default(), member))
+            .collect()
+    };
+
+    let has_more = values.len() > count;
+    values.truncate(count);
+
+    info!(key, total, len = values.len(), "获取有序集合范围成功");
+    Ok(ZRangePage {
+        total,
+        offset,
+        has_more,
+        values,
+    })
+}
+
+/// 重命名键
+#[tauri::command]
+#[instrument(skip(state, history, snapshots))]
 pub async fn rename_key(
     state: State<'_, RedisState>,
     history: State<'_, History>,
+    snapshots: State<'_, SnapshotStore>,
     id: String,
     db: u8,
     key: String,
@@ -420,6 +650,8 @@ pub async fn rename_key(
     let mut redis_state = state.0.lock().await;
     let (con, config) = redis_state.get_con_and_config(&id)?;
     select_db(config, db, con, &history).await?;
+
+    snapshots.capture(con, &id, db, "rename", &key).await;
     redis::pipe()
         .rename_nx(&key, &new_key)
         .log(history.0.clone(), config)
@@ -431,12 +663,135 @@ pub async fn rename_key(
     Ok(())
 }
 
-/// 设置key
+/// 复制键, 可复制到其他数据库
+///
+/// 优先使用`COPY`(redis 6.2+), 不支持或跨槽时回退为`DUMP` + `RESTORE`并保留过期时间
+#[tauri::command]
+#[instrument(skip(state, history))]
+pub async fn copy_key(
+    state: State<'_, RedisState>,
+    history: State<'_, History>,
+    id: String,
+    db: u8,
+    copyinfo: CopyKeyInfo,
+) -> Result<()> {
+    let mut redis_state = state.0.lock().await;
+    let (con, config) = redis_state.get_con_and_config(&id)?;
+    select_db(config, db, con, &history).await?;
+
+    let CopyKeyInfo {
+        key,
+        new_key,
+        target_db,
+        replace,
+    } = copyinfo;
+    let target_db = target_db.filter(|target| *target != db);
+    if config.cluster && target_db.is_some() {
+        return Err("集群模式不支持跨数据库复制".into());
+    }
+
+    let mut cmd = redis::cmd("COPY");
+    cmd.arg(&key).arg(&new_key);
+    if let Some(target_db) = target_db {
+        cmd.arg("DB").arg(target_db);
+    }
+    if replace {
+        cmd.arg("REPLACE");
+    }
+
+    let res: redis::RedisResult<bool> = cmd.log(history.0.clone(), config).query_async(con).await;
+    match res {
+        Ok(true) => {}
+        Ok(false) => return Err(copy_failed_reason(con, &key).await),
+        Err(err) if is_unsupported_error(&err) || err.kind() == ErrorKind::CrossSlot => {
+            info!(?err, "COPY不可用, 回退为DUMP + RESTORE");
+
+            let dump = dump_key(con, &key)
+                .await?
+                .context(format!("key不存在: {key}"))?;
+            history.add_log_vec(LogArgs!["dump", &key], config);
+            history.add_log_vec(LogArgs!["pttl", &key], config);
+
+            if let Some(target_db) = target_db {
+                select_db(config, target_db, con, &history).await?;
+            }
+            let res: redis::RedisResult<()> =
+                dump.restore_cmd(&new_key, replace).query_async(con).await;
+            if let Some(target_db) = target_db {
+                history.add_log(format!("[db{target_db}] restore {new_key}"), config);
+                select_db(config, db, con, &history).await?;
+            } else {
+                history.add_log(format!("restore {new_key}"), config);
+            }
+
+            match res {
+                Err(err) if err.code() == Some("BUSYKEY") => {
+                    return Err(format!("目标key已存在: {new_key}").into())
+                }
+                res => res?,
+            }
+        }
+        Err(err) => return Err(err.into()),
+    }
+
+    info!(?key, ?new_key, ?target_db, "复制key成功");
+    Ok(())
+}
+
+/// COPY返回0时, 区分源键不存在和目标键已存在
+async fn copy_failed_reason(con: &mut RedisConnection, key: &str) -> crate::error::SerializeError {
+    match con.exists::<_, bool>(key).await {
+        Ok(false) => format!("key不存在: {key}").into(),
+        Ok(true) => "目标key已存在".into(),
+        Err(err) => err.into(),
+    }
+}
+
+/// 移动键到其他数据库
 #[tauri::command]
 #[instrument(skip(state, history))]
+pub async fn move_key(
+    state: State<'_, RedisState>,
+    history: State<'_, History>,
+    id: String,
+    db: u8,
+    key: String,
+    target_db: u8,
+) -> Result<()> {
+    let mut redis_state = state.0.lock().await;
+    let (con, config) = redis_state.get_con_and_config(&id)?;
+    select_db(config, db, con, &history).await?;
+
+    if config.cluster {
+        return Err("集群模式不支持移动到其他数据库".into());
+    }
+
+    if target_db == db {
+        return Err("目标数据库不能与当前数据库相同".into());
+    }
+
+    let moved: bool = redis::cmd("MOVE")
+        .arg(&key)
+        .arg(target_db)
+        .log(history.0.clone(), config)
+        .query_async(con)
+        .await?;
+    if !moved {
+        return Err(copy_failed_reason(con, &key).await);
+    }
+
+    info!(?key, target_db, "移动key成功");
+    Ok(())
+}
+
+/// 设置key
+#[tauri::command]
+#[instrument(skip(state, history, undo, snapshots))]
 pub async fn set_key(
     state: State<'_, RedisState>,
     history: State<'_, History>,
+    undo: State<'_, UndoState>,
+    snapshots: State<'_, SnapshotStore>,
     id: String,
     db: u8,
     keyinfo: AddKeyInfo,
@@ -445,17 +800,19 @@ pub async fn set_key(
     let (con, config) = redis_state.get_con_and_config(&id)?;
     select_db(config, db, con, &history).await?;
 
-    let expired: isize = con.ttl(&keyinfo.key).await?;
-    history.add_log_vec(LogArgs!["ttl", &keyinfo.key], config);
+    // 键不存在时没有需要撤销的内容
+    let entry = undo_entry(con, config, &history, db, "set", &keyinfo.key).await;
+    undo.push(&id, entry);
+    snapshots.capture(con, &id, db, "set", &keyinfo.key).await;
 
+    // 除了SET外, 其他写命令都不会改变键的过期时间
     match keyinfo.r#type.as_str() {
-        "string" => {
-            con.set(&keyinfo.key, &keyinfo.value).await?;
-            history.add_log_vec(LogArgs!["set", &keyinfo.key, &keyinfo.value], config);
-        }
+        "string" => set_string_keep_ttl(con, config, &history, &keyinfo).await?,
         "list" => {
-            // 键不存在
-            if expired == -2 {
+            let exists: bool = con.exists(&keyinfo.key).await?;
+            history.add_log_vec(LogArgs!["exists", &keyinfo.key], config);
+
+            if !exists {
                 con.lpush(&keyinfo.key, &keyinfo.value).await?;
                 history.add_log_vec(
                     vec![
@@ -535,21 +892,48 @@ pub async fn set_key(
         _ => return Err(format!("不支持的类型: {}", keyinfo.r#type).into()),
     };
 
-    match expired {
-        // 键不存在(-2)
-        -2 => {
-            con.persist(&keyinfo.key).await?;
-            history.add_log_vec(LogArgs!["persist", &keyinfo.key], config);
-        }
-        // 已持久化的(-1)
-        -1 => return Ok(()),
-        _ => {
-            con.expire(&keyinfo.key, expired as usize).await?;
-            history.add_log_vec(LogArgs!["persist", &keyinfo.key, expired], config);
+    info!(?keyinfo, "设置key成功: ");
+    Ok(())
+}
+
+/// 设置字符串的值并保留原有的过期时间
+///
+/// 优先使用`SET ... KEEPTTL`(redis 6.0+), 不支持时回退为`PTTL` + `SET` + `PEXPIRE`
+async fn set_string_keep_ttl(
+    con: &mut RedisConnection,
+    config: &RedisConfig,
+    history: &History,
+    keyinfo: &AddKeyInfo,
+) -> Result<()> {
+    let res: redis::RedisResult<()> = redis::cmd("SET")
+        .arg(&keyinfo.key)
+        .arg(&keyinfo.value)
+        .arg("KEEPTTL")
+        .log(history.0.clone(), config)
+        .query_async(con)
+        .await;
+
+    match res {
+        Ok(()) => return Ok(()),
+        Err(err) if is_unsupported_error(&err) => {
+            info!(?err, "服务端不支持KEEPTTL, 回退为PTTL + PEXPIRE");
         }
-    };
+        Err(err) => return Err(err.into()),
+    }
+
+    let pttl: i64 = redis::cmd("PTTL")
+        .arg(&keyinfo.key)
+        .log(history.0.clone(), config)
+        .query_async(con)
+        .await?;
+
+    let mut pipe = redis::pipe();
+    pipe.set(&keyinfo.key, &keyinfo.value).ignore();
+    if pttl > 0 {
+        pipe.pexpire(&keyinfo.key, pttl as usize).ignore();
+    }
+    pipe.log(history.0.clone(), config).query_async(con).await?;
 
-    info!(?keyinfo, "设置key成功: ");
     Ok(())
 }
 
@@ -572,15 +956,108 @@ pub async fn set_key_ttl(
         return Err("过期的值不能小于-1".into());
     }
 
-    if ttl == -1 {
-        con.persist(&key).await?;
-        history.add_log_vec(LogArgs!["persist", &key], config);
+    let expire = if ttl == -1 {
+        KeyExpire::Persist
     } else {
-        con.expire(&key, ttl as usize).await?;
-        history.add_log_vec(LogArgs!["expire", &key, ttl], config);
-    }
+        KeyExpire::Seconds(ttl)
+    };
+    apply_expire(con, config, &history, &key, &expire).await?;
 
     info!(?key, ?ttl, "设置key的ttl成功");
 
     Ok(())
 }
+
+/// 设置键的过期时间, 支持毫秒以及绝对时间
+#[tauri::command]
+#[instrument(skip(state, history))]
+pub async fn set_key_expire(
+    state: State<'_, RedisState>,
+    history: State<'_, History>,
+    id: String,
+    db: u8,
+    key: String,
+    expire: KeyExpire,
+) -> Result<i64> {
+    let mut redis_state = state.0.lock().await;
+    let (con, config) = redis_state.get_con_and_config(&id)?;
+    select_db(config, db, con, &history).await?;
+
+    apply_expire(con, config, &history, &key, &expire).await?;
+
+    let pttl: i64 = redis::cmd("PTTL")
+        .arg(&key)
+        .log(history.0.clone(), config)
+        .query_async(con)
+        .await?;
+
+    info!(?key, ?expire, pttl, "设置key的过期时间成功");
+
+    Ok(pttl)
+}
+
+/// 在修改或删除键之前保存DUMP和PTTL, 键不存在或保存失败时返回空的记录, 不影响后续操作
+async fn undo_entry(
+    con: &mut RedisConnection,
+    config: &RedisConfig,
+    history: &History,
+    db: u8,
+    op: &str,
+    key: &str,
+) -> UndoEntry {
+    let mut entry = UndoEntry::new(op, db);
+    match dump_key(con, key).await {
+        Ok(Some(dump)) => {
+            entry.push(key.to_string(), dump);
+            history.add_log_vec(LogArgs!["dump", key], config);
+        }
+        Ok(None) => {}
+        Err(err) => warn!(?err, key, "保存撤销数据失败"),
+    }
+    entry
+}
+
+/// 执行过期时间对应的命令
+async fn apply_expire(
+    con: &mut RedisConnection,
+    config: &RedisConfig,
+    history: &History,
+    key: &str,
+    expire: &KeyExpire,
+) -> Result<()> {
+    let updated: bool = expire_cmd(key, expire)?
+        .log(history.0.clone(), config)
+        .query_async(con)
+        .await?;
+
+    // PERSIST在键本身没有过期时间时也会返回0
+    if !updated && !matches!(expire, KeyExpire::Persist) {
+        return Err(format!("key不存在: {key}").into());
+    }
+
+    Ok(())
+}
+
+/// 生成过期时间对应的命令, 返回1表示设置成功
+pub(crate) fn expire_cmd(key: &str, expire: &KeyExpire) -> Result<Cmd> {
+    let (name, value) = match *expire {
+        KeyExpire::Persist => ("PERSIST", None),
+        KeyExpire::Seconds(seconds) => ("EXPIRE", Some(seconds)),
+        KeyExpire::Milliseconds(ms) => ("PEXPIRE", Some(ms)),
+        KeyExpire::At(ts) => ("EXPIREAT", Some(ts)),
+        KeyExpire::AtMilliseconds(ts) => ("PEXPIREAT", Some(ts)),
+    };
+
+    if value.is_some_and(|v| v < 0) {
+        return Err("过期的值不能小于0".into());
+    }
+
+    let mut cmd = redis::cmd(name);
+    cmd.arg(key).arg(value);
+    Ok(cmd)
+}
+
+/// 根据剩余毫秒数计算绝对过期时间(unix时间戳, 毫秒)
+fn expire_at(pttl: i64) -> Option<i64> {
+    (pttl >= 0).then(|| Local::now().timestamp_millis() + pttl)
+}
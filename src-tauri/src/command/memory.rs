@@ -0,0 +1,141 @@
+use crate::{
+    analysis::{MemoryAggregator, DEFAULT_DELAY, DEFAULT_TOP},
+    config::RedisConfig,
+    error::Result,
+    job::{spawn_job, JobContext, Jobs},
+    model::{AnalyzeOptions, AnalyzeProgress, KeyMemory, MemoryReport},
+    scan::{KeyScanner, SCAN_COUNT},
+    value::len_cmd,
+    History, RedisState, SharedConnection,
+};
+use futures::future::join_all;
+use redis::RedisResult;
+use std::time::Duration;
+use tauri::{State, Window};
+use tracing::{info, instrument};
+
+use super::is_unsupported_error;
+
+/// 在后台分析匹配的键占用的内存, 返回任务id
+#[tauri::command]
+#[instrument(skip(window, state, history, jobs))]
+pub async fn analyze_memory(
+    window: Window,
+    state: State<'_, RedisState>,
+    history: State<'_, History>,
+    jobs: State<'_, Jobs>,
+    id: String,
+    db: u8,
+    options: AnalyzeOptions,
+) -> Result<String> {
+    let config = state.0.lock().await.get_config(&id)?;
+    let con = SharedConnection::open(&config, db).await?;
+
+    let pattern = options.pattern.as_deref().unwrap_or("*");
+    history.add_log(format!("analyze memory db{db} {pattern}"), &config);
+
+    let job_id = spawn_job(window, &jobs, "memory", |ctx| {
+        analyze(ctx, config, con, db, options)
+    });
+
+    info!(job_id, "开始分析内存");
+    Ok(job_id)
+}
+
+async fn analyze(
+    ctx: JobContext,
+    config: RedisConfig,
+    con: SharedConnection,
+    db: u8,
+    options: AnalyzeOptions,
+) -> Result<MemoryReport> {
+    let delay = Duration::from_millis(options.delay.unwrap_or(DEFAULT_DELAY));
+    let pattern = options.pattern.as_deref().unwrap_or("*");
+    let scan_count = options.scan_count.unwrap_or(SCAN_COUNT).max(1);
+    let mut aggregator = MemoryAggregator::new(
+        &config.split,
+        options.depth.unwrap_or(1),
+        options.top.unwrap_or(DEFAULT_TOP),
+    );
+
+    let mut scanner = KeyScanner::new(&config, &con, pattern, scan_count).await?;
+    while let Some((mut node, keys)) = scanner.next_batch().await? {
+        if ctx.is_cancelled() {
+            break;
+        }
+
+        for key in key_memory(&mut node, db, keys).await? {
+            aggregator.add(key);
+        }
+
+        let (nodes, total_nodes) = scanner.nodes();
+        ctx.progress(&AnalyzeProgress {
+            nodes,
+            total_nodes,
+            keys: aggregator.keys(),
+            bytes: aggregator.bytes(),
+        });
+
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    let report = aggregator.report();
+    info!(job_id = ctx.id(), keys = report.keys, "内存分析完成");
+    Ok(report)
+}
+
+/// 查询一批键的类型, 过期时间, 内存占用和元素数量, 忽略扫描后已被删除的键
+async fn key_memory(
+    con: &mut SharedConnection,
+    db: u8,
+    keys: Vec<String>,
+) -> Result<Vec<KeyMemory>> {
+    let mut pipe = redis::pipe();
+    for key in &keys {
+        pipe.key_type(key)
+            .pttl(key)
+            .cmd("MEMORY")
+            .arg("USAGE")
+            .arg(key);
+    }
+    let values: Vec<(String, i64, Option<u64>)> = match pipe.query_async(con).await {
+        Ok(values) => values,
+        Err(err) if is_unsupported_error(&err) => {
+            return Err(format!("服务器不支持MEMORY USAGE命令: {err}").into())
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    // 元素数量单独查询, 类型在扫描后发生变化时只忽略该键
+    let lens = join_all(keys.iter().zip(&values).map(|(key, (typ, _, _))| {
+        let mut con = con.clone();
+        let cmd = len_cmd(typ, key);
+        async move {
+            let Some(cmd) = cmd else {
+                return Ok(0);
+            };
+            let res: RedisResult<u64> = cmd.query_async(&mut con).await;
+            res
+        }
+    }))
+    .await;
+
+    Ok(keys
+        .into_iter()
+        .zip(values)
+        .zip(lens)
+        .filter_map(|((key, (typ, ttl, bytes)), len)| {
+            Some(KeyMemory {
+                db: db as u32,
+                key,
+                r#type: typ,
+                encoding: None,
+                bytes: bytes?,
+                elements: len.unwrap_or(0),
+                ttl,
+            })
+        })
+        .collect())
+}
@@ -0,0 +1,160 @@
+use crate::{
+    config::RedisConfig,
+    dump::{dump_keys, KeyDump},
+    error::Result,
+    job::{spawn_job, JobContext, Jobs},
+    model::{ConflictPolicy, KeyError, MigrateOptions, MigrateProgress},
+    scan::{KeyScanner, SCAN_COUNT},
+    History, RedisState, SharedConnection,
+};
+use futures::{stream, StreamExt};
+use tauri::{State, Window};
+use tracing::{info, instrument};
+
+/// 默认每批处理的键数量
+const DEFAULT_BATCH_SIZE: usize = 200;
+/// 默认同时执行的RESTORE数量
+const DEFAULT_CONCURRENCY: usize = 16;
+/// 进度中最多保留的失败记录
+const MAX_FAILURES: usize = 100;
+
+/// 在两个连接之间迁移匹配的键, 返回任务id
+#[tauri::command]
+#[instrument(skip(window, state, history, jobs))]
+pub async fn migrate_keys(
+    window: Window,
+    state: State<'_, RedisState>,
+    history: State<'_, History>,
+    jobs: State<'_, Jobs>,
+    options: MigrateOptions,
+) -> Result<String> {
+    let (source_config, target_config) = {
+        let redis_state = state.0.lock().await;
+        (
+            redis_state.get_config(&options.source_id)?,
+            redis_state.get_config(&options.target_id)?,
+        )
+    };
+
+    let source = SharedConnection::open(&source_config, options.source_db).await?;
+    let target = SharedConnection::open(&target_config, options.target_db).await?;
+
+    history.add_log(
+        format!(
+            "migrate db{} {} => [{}] db{}",
+            options.source_db, options.pattern, target_config.name, options.target_db
+        ),
+        &source_config,
+    );
+
+    let job_id = spawn_job(window, &jobs, "migrate", |ctx| {
+        migrate(ctx, source_config, source, target, options)
+    });
+
+    info!(job_id, "开始迁移");
+    Ok(job_id)
+}
+
+async fn migrate(
+    ctx: JobContext,
+    source_config: RedisConfig,
+    source: SharedConnection,
+    target: SharedConnection,
+    options: MigrateOptions,
+) -> Result<MigrateProgress> {
+    let batch_size = options.batch_size.unwrap_or(DEFAULT_BATCH_SIZE).max(1);
+    let concurrency = options.concurrency.unwrap_or(DEFAULT_CONCURRENCY).max(1);
+    let conflict = options.conflict;
+    let suffix = options.rename_suffix().to_string();
+
+    let mut progress = MigrateProgress::default();
+    let mut scanner =
+        KeyScanner::new(&source_config, &source, &options.pattern, SCAN_COUNT).await?;
+
+    while let Some((mut node, keys)) = scanner.next_batch().await? {
+        for keys in keys.chunks(batch_size) {
+            if ctx.is_cancelled() {
+                return Ok(progress);
+            }
+
+            progress.scanned += keys.len() as u64;
+            let dumps = dump_keys(&mut node, keys).await?;
+
+            let results: Vec<_> = stream::iter(keys.iter().cloned().zip(dumps))
+                .map(|(key, dump)| {
+                    let target = target.clone();
+                    let suffix = suffix.clone();
+                    async move {
+                        let result = restore(target, &key, dump, conflict, &suffix).await;
+                        (key, result)
+                    }
+                })
+                .buffer_unordered(concurrency)
+                .collect()
+                .await;
+
+            for (key, result) in results {
+                match result {
+                    Ok(RestoreResult::Migrated) => progress.migrated += 1,
+                    Ok(RestoreResult::Renamed) => progress.renamed += 1,
+                    Ok(RestoreResult::Skipped) => progress.skipped += 1,
+                    Err(err) => {
+                        progress.failed += 1;
+                        if progress.failures.len() < MAX_FAILURES {
+                            progress.failures.push(KeyError {
+                                key,
+                                message: err.to_string(),
+                            });
+                        }
+                    }
+                }
+            }
+
+            ctx.progress(&progress);
+        }
+    }
+
+    info!(job_id = ctx.id(), ?progress, "迁移完成");
+    Ok(progress)
+}
+
+enum RestoreResult {
+    Migrated,
+    Renamed,
+    Skipped,
+}
+
+/// 按冲突策略将键还原到目标连接
+async fn restore(
+    mut target: SharedConnection,
+    key: &str,
+    dump: Option<KeyDump>,
+    conflict: ConflictPolicy,
+    rename_suffix: &str,
+) -> redis::RedisResult<RestoreResult> {
+    // 扫描之后键被删除或已过期
+    let Some(dump) = dump else {
+        return Ok(RestoreResult::Skipped);
+    };
+
+    let replace = matches!(conflict, ConflictPolicy::Replace);
+    let res: redis::RedisResult<()> = dump
+        .restore_cmd(key, replace)
+        .query_async(&mut target)
+        .await;
+
+    match res {
+        Ok(()) => Ok(RestoreResult::Migrated),
+        Err(err) if err.code() == Some("BUSYKEY") => match conflict {
+            ConflictPolicy::Rename => {
+                let new_key = format!("{key}{rename_suffix}");
+                dump.restore_cmd(&new_key, false)
+                    .query_async(&mut target)
+                    .await?;
+                Ok(RestoreResult::Renamed)
+            }
+            _ => Ok(RestoreResult::Skipped),
+        },
+        Err(err) => Err(err),
+    }
+}
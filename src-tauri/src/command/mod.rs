@@ -1,12 +1,46 @@
 pub mod conn;
+pub mod debug;
+pub mod delete;
+pub mod diff;
+pub mod export;
+pub mod function;
+pub mod hot_keys;
+pub mod import;
+pub mod job;
 pub mod key_ops;
+pub mod memory;
+pub mod migrate;
+pub mod namespace;
+pub mod offline;
+pub mod script;
+pub mod search;
+pub mod snapshot;
 pub mod state;
 pub use conn::*;
+pub use debug::*;
+pub use delete::*;
+pub use diff::*;
+pub use export::*;
+pub use function::*;
+pub use hot_keys::*;
+pub use import::*;
+pub use job::*;
 pub use key_ops::*;
-use redis::ConnectionInfo;
+pub use memory::*;
+pub use migrate::*;
+pub use namespace::*;
+pub use offline::*;
+use redis::{aio::ConnectionLike, Cmd, ConnectionInfo, ErrorKind, FromRedisValue, RedisError};
+pub use script::*;
+pub use search::*;
+pub use snapshot::*;
 pub use state::*;
 pub mod terminal;
+pub mod ttl;
+pub mod undo;
 pub use terminal::*;
+pub use ttl::*;
+pub use undo::*;
 
 use crate::{config::RedisConfig, error::Result, node_info::NodesInfo};
 
@@ -29,9 +63,9 @@ pub async fn select_db<'a>(
     Ok(())
 }
 
-pub async fn get_cluster_clients(
+pub async fn get_cluster_clients<C: ConnectionLike + Send>(
     config: &RedisConfig,
-    con: &mut RedisConnection,
+    con: &mut C,
 ) -> Result<Vec<redis::Client>> {
     let nodes: NodesInfo = redis::cmd("CLUSTER").arg("nodes").query_async(con).await?;
 
@@ -59,3 +93,60 @@ pub async fn get_cluster_clients(
 
     Ok(clients)
 }
+
+/// 执行命令, 集群模式下在每个主节点上执行并返回最后一个节点的结果
+///
+/// 用于脚本缓存和函数库等保存在每个节点上的数据; 某个节点失败时仍在其余节点上执行,
+/// 返回的错误中列出成功和失败的节点
+pub async fn query_all_masters<T: FromRedisValue>(
+    config: &RedisConfig,
+    con: &mut RedisConnection,
+    cmd: &Cmd,
+) -> Result<T> {
+    if !config.cluster {
+        return Ok(cmd.query_async(con).await?);
+    }
+
+    let mut res = None;
+    let mut succeeded = vec![];
+    let mut failed = vec![];
+    for client in get_cluster_clients(config, con).await? {
+        let addr = client.get_connection_info().addr.to_string();
+        let node_res = match client.get_async_connection().await {
+            Ok(mut node) => cmd.query_async(&mut node).await,
+            Err(err) => Err(err),
+        };
+        match node_res {
+            Ok(value) => {
+                res = Some(value);
+                succeeded.push(addr);
+            }
+            Err(err) => failed.push(format!("{addr}({err})")),
+        }
+    }
+
+    if !failed.is_empty() {
+        return Err(format!(
+            "部分节点执行失败, 成功: [{}], 失败: [{}]",
+            succeeded.join(", "),
+            failed.join(", ")
+        )
+        .into());
+    }
+    res.ok_or_else(|| "集群中没有主节点".into())
+}
+
+/// 判断是否为服务端不支持的命令或参数(版本过低, 命令被禁用或重命名)
+pub fn is_unsupported_error(err: &RedisError) -> bool {
+    if !matches!(
+        err.kind(),
+        ErrorKind::ResponseError | ErrorKind::ExtensionError
+    ) {
+        return false;
+    }
+
+    let detail = err.detail().unwrap_or_default().to_lowercase();
+    detail.starts_with("unknown command")
+        || detail.starts_with("unknown subcommand")
+        || detail.starts_with("syntax error")
+}
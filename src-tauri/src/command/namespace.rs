@@ -0,0 +1,299 @@
+use crate::{
+    analysis::DEFAULT_DELAY,
+    config::RedisConfig,
+    dump::dump_key,
+    error::Result,
+    glob,
+    job::{spawn_job, JobContext, Jobs},
+    model::{
+        KeyError, NamespaceKey, NamespaceOp, NamespaceOptions, NamespacePreview, NamespaceProgress,
+    },
+    scan::{preview_keys, KeyScanner, SCAN_COUNT},
+    History, RedisState, SharedConnection,
+};
+use futures::future::join_all;
+use redis::{AsyncCommands, ErrorKind, RedisResult};
+use std::time::Duration;
+use tauri::{State, Window};
+use tracing::{info, instrument};
+
+use super::{expire_cmd, is_unsupported_error};
+
+/// 默认每批处理的键数量
+const DEFAULT_BATCH_SIZE: usize = 100;
+/// 默认预览的键数量
+const DEFAULT_PREVIEW: usize = 100;
+/// 进度中最多保留的失败记录
+const MAX_FAILURES: usize = 100;
+
+/// 预览命名空间操作影响的键
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn preview_namespace(
+    state: State<'_, RedisState>,
+    id: String,
+    db: u8,
+    options: NamespaceOptions,
+    limit: Option<usize>,
+) -> Result<NamespacePreview> {
+    check_options(&options)?;
+    let config = state.0.lock().await.get_config(&id)?;
+    let con = SharedConnection::open(&config, db).await?;
+
+    let (count, keys) = preview_keys(
+        &config,
+        &con,
+        &pattern(&options.prefix),
+        limit.unwrap_or(DEFAULT_PREVIEW),
+    )
+    .await?;
+
+    let keys = keys
+        .into_iter()
+        .map(|key| NamespaceKey {
+            new_key: new_key(&options, &key),
+            key,
+        })
+        .collect();
+
+    Ok(NamespacePreview { count, keys })
+}
+
+/// 在后台对命名空间中的所有键执行操作, 返回任务id
+#[tauri::command]
+#[instrument(skip(window, state, history, jobs))]
+pub async fn run_namespace_op(
+    window: Window,
+    state: State<'_, RedisState>,
+    history: State<'_, History>,
+    jobs: State<'_, Jobs>,
+    id: String,
+    db: u8,
+    options: NamespaceOptions,
+) -> Result<String> {
+    check_options(&options)?;
+    let config = state.0.lock().await.get_config(&id)?;
+    let con = SharedConnection::open(&config, db).await?;
+
+    let pattern = pattern(&options.prefix);
+    let log = match options.op {
+        NamespaceOp::Rename { ref to, .. } => format!("rename {pattern} => {to}*"),
+        NamespaceOp::Expire { ref expire } => format!("expire {pattern} {expire:?}"),
+        NamespaceOp::Stats => format!("stats {pattern}"),
+    };
+    history.add_log(format!("[db{db}] {log}"), &config);
+
+    let job_id = spawn_job(window, &jobs, "namespace", |ctx| {
+        run(ctx, config, con, options)
+    });
+
+    info!(job_id, "开始命名空间操作");
+    Ok(job_id)
+}
+
+fn check_options(options: &NamespaceOptions) -> Result<()> {
+    if options.prefix.is_empty() {
+        return Err("命名空间前缀不能为空".into());
+    }
+    if let NamespaceOp::Rename { ref to, .. } = options.op {
+        // 新键会再次被SCAN扫描到并重复重命名, 例如a:b:重命名为a:时, a:b:b:1会变为a:b:1
+        if to.starts_with(&options.prefix) || options.prefix.starts_with(to.as_str()) {
+            return Err("新前缀与原前缀不能互为前缀".into());
+        }
+    }
+    if let NamespaceOp::Expire { ref expire } = options.op {
+        expire_cmd("", expire)?;
+    }
+
+    Ok(())
+}
+
+/// 前缀对应的SCAN MATCH模式
+fn pattern(prefix: &str) -> String {
+    format!("{}*", glob::escape(prefix))
+}
+
+fn new_key(options: &NamespaceOptions, key: &str) -> Option<String> {
+    match options.op {
+        NamespaceOp::Rename { ref to, .. } => key
+            .strip_prefix(options.prefix.as_str())
+            .map(|rest| format!("{to}{rest}")),
+        _ => None,
+    }
+}
+
+async fn run(
+    ctx: JobContext,
+    config: RedisConfig,
+    con: SharedConnection,
+    options: NamespaceOptions,
+) -> Result<NamespaceProgress> {
+    let batch_size = options.batch_size.unwrap_or(DEFAULT_BATCH_SIZE).max(1);
+    let delay = Duration::from_millis(options.delay.unwrap_or(DEFAULT_DELAY));
+
+    let mut progress = NamespaceProgress::default();
+    if let NamespaceOp::Stats = options.op {
+        progress.bytes = Some(0);
+    }
+
+    let mut scanner = KeyScanner::new(&config, &con, &pattern(&options.prefix), SCAN_COUNT).await?;
+    while let Some((mut node, keys)) = scanner.next_batch().await? {
+        for keys in keys.chunks(batch_size) {
+            if ctx.is_cancelled() {
+                return Ok(progress);
+            }
+
+            progress.scanned += keys.len() as u64;
+            match options.op {
+                NamespaceOp::Rename { ref to, replace } => {
+                    let results = join_all(keys.iter().map(|key| {
+                        let mut con = con.clone();
+                        let new_key = format!("{to}{}", &key[options.prefix.len()..]);
+                        async move { rename(&mut con, key, &new_key, replace).await }
+                    }))
+                    .await;
+                    for (key, result) in keys.iter().zip(results) {
+                        record(&mut progress, key, result);
+                    }
+                }
+                NamespaceOp::Expire { ref expire } => {
+                    let mut pipe = redis::pipe();
+                    for key in keys {
+                        pipe.add_command(expire_cmd(key, expire)?);
+                    }
+                    let updated: Vec<bool> = pipe.query_async(&mut node).await?;
+                    for updated in updated {
+                        if updated {
+                            progress.processed += 1;
+                        } else {
+                            progress.skipped += 1;
+                        }
+                    }
+                }
+                NamespaceOp::Stats => stats(&mut node, keys, &mut progress).await?,
+            }
+
+            ctx.progress(&progress);
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+
+    info!(job_id = ctx.id(), ?progress, "命名空间操作完成");
+    Ok(progress)
+}
+
+fn record(progress: &mut NamespaceProgress, key: &str, result: RedisResult<bool>) {
+    match result {
+        Ok(true) => progress.processed += 1,
+        Ok(false) => progress.skipped += 1,
+        Err(err) => {
+            progress.failed += 1;
+            if progress.failures.len() < MAX_FAILURES {
+                progress.failures.push(KeyError {
+                    key: key.to_string(),
+                    message: err.to_string(),
+                });
+            }
+        }
+    }
+}
+
+/// 重命名单个键, 键不存在或新键已存在时返回false
+///
+/// 集群模式下新旧键不在同一个槽时RENAME会返回CROSSSLOT, 此时通过DUMP + RESTORE + DEL迁移
+async fn rename(
+    con: &mut SharedConnection,
+    key: &str,
+    new_key: &str,
+    replace: bool,
+) -> RedisResult<bool> {
+    let cmd = if replace { "RENAME" } else { "RENAMENX" };
+    let res: RedisResult<redis::Value> =
+        redis::cmd(cmd).arg(key).arg(new_key).query_async(con).await;
+    match res {
+        Ok(redis::Value::Int(0)) => Ok(false),
+        Ok(_) => Ok(true),
+        Err(err) if err.kind() == ErrorKind::CrossSlot => {
+            rename_cross_slot(con, key, new_key, replace).await
+        }
+        // 扫描后键已被删除
+        Err(err)
+            if err
+                .detail()
+                .is_some_and(|detail| detail.contains("no such key")) =>
+        {
+            Ok(false)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+async fn rename_cross_slot(
+    con: &mut SharedConnection,
+    key: &str,
+    new_key: &str,
+    replace: bool,
+) -> RedisResult<bool> {
+    let Some(dump) = dump_key(con, key).await? else {
+        return Ok(false);
+    };
+
+    let res: RedisResult<()> = dump.restore_cmd(new_key, replace).query_async(con).await;
+    match res {
+        Err(err) if err.code() == Some("BUSYKEY") => return Ok(false),
+        res => res?,
+    }
+    con.del(key).await?;
+
+    Ok(true)
+}
+
+/// 统计一批键占用的内存, 不支持MEMORY USAGE时只统计数量
+async fn stats(
+    con: &mut SharedConnection,
+    keys: &[String],
+    progress: &mut NamespaceProgress,
+) -> Result<()> {
+    if progress.bytes.is_some() {
+        let mut pipe = redis::pipe();
+        for key in keys {
+            pipe.cmd("MEMORY").arg("USAGE").arg(key);
+        }
+        let res: RedisResult<Vec<Option<u64>>> = pipe.query_async(con).await;
+        match res {
+            Ok(values) => {
+                for bytes in values {
+                    match bytes {
+                        Some(bytes) => {
+                            progress.processed += 1;
+                            progress.bytes = progress.bytes.map(|total| total + bytes);
+                        }
+                        None => progress.skipped += 1,
+                    }
+                }
+                return Ok(());
+            }
+            Err(err) if is_unsupported_error(&err) => progress.bytes = None,
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    let exists: Vec<bool> = {
+        let mut pipe = redis::pipe();
+        for key in keys {
+            pipe.exists(key);
+        }
+        pipe.query_async(con).await?
+    };
+    for exists in exists {
+        if exists {
+            progress.processed += 1;
+        } else {
+            progress.skipped += 1;
+        }
+    }
+
+    Ok(())
+}
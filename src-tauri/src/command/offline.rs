@@ -0,0 +1,366 @@
+use crate::{
+    analysis::{MemoryAggregator, DEFAULT_TOP},
+    config::OfflineConfig,
+    error::Result,
+    glob::glob_match,
+    model::{
+        HashResult, KeyContentDetail, KeyInfo, KeyMemory, KeyValue, MemoryReport,
+        MemoryReportOptions, RdbDbSummary, RdbSummary, RedisValue, StreamResult,
+    },
+    rdb::{memory, RdbEntry, RdbFile},
+    OfflineState,
+};
+use chrono::Local;
+use serde_json::json;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufWriter, Write},
+    sync::Arc,
+};
+use tauri::State;
+use tracing::{info, instrument};
+
+/// 与在线连接保持一致, stream只返回最新的200条
+const STREAM_DETAIL_COUNT: usize = 200;
+
+/// 离线连接, 只读地浏览rdb文件中的键
+#[derive(Debug)]
+pub struct OfflineInstance {
+    pub config: OfflineConfig,
+    pub rdb: RdbFile,
+}
+
+impl OfflineInstance {
+    fn entry(&self, db: u8, key: &str) -> Result<&RdbEntry> {
+        self.rdb
+            .dbs
+            .get(&(db as u32))
+            .and_then(|keys| keys.get(key))
+            .ok_or_else(|| format!("key不存在: {key}").into())
+    }
+
+    /// 剩余过期时间(毫秒)以rdb文件生成的时间计算, 没有生成时间时以当前时间计算
+    fn pttl(&self, entry: &RdbEntry) -> i64 {
+        let now = self
+            .rdb
+            .created_at()
+            .unwrap_or_else(|| Local::now().timestamp_millis());
+        entry
+            .expire_at
+            .map(|expire_at| (expire_at - now).max(0))
+            .unwrap_or(-1)
+    }
+
+    pub fn keys(&self, db: u8) -> Vec<String> {
+        self.rdb
+            .dbs
+            .get(&(db as u32))
+            .map(|keys| keys.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn key_type(&self, db: u8, key: &str) -> String {
+        self.entry(db, key)
+            .map(|entry| entry.value.type_name().to_string())
+            .unwrap_or_else(|_| "none".to_string())
+    }
+
+    pub fn key_info(&self, db: u8, key: &str) -> Result<KeyInfo> {
+        let entry = self.entry(db, key)?;
+        let typ = entry.value.type_name();
+        let pttl = self.pttl(entry);
+
+        Ok(KeyInfo {
+            key: key.to_string(),
+            r#type: typ.to_string(),
+            label: typ[0..1].to_uppercase() + &typ[1..],
+            ttl: if pttl < 0 { pttl } else { pttl / 1000 },
+            pttl,
+            expire_at: entry.expire_at,
+            encoding: Some(entry.encoding.to_string()),
+            memory: Some(memory::estimate(key, entry)),
+            idle_time: None,
+            freq: None,
+            refcount: None,
+            elements: Some(entry.value.len() as u64),
+        })
+    }
+
+    /// search为哈希, 集合和有序集合的MATCH模式
+    pub fn key_detail(&self, db: u8, key: &str, search: Option<&str>) -> Result<KeyContentDetail> {
+        let info = self.key_info(db, key)?;
+        let (size, mut value) = detail_value(&self.entry(db, key)?.value);
+        if let Some(pattern) = search.filter(|search| !search.is_empty()) {
+            filter_value(&mut value, pattern);
+        }
+
+        Ok(KeyContentDetail {
+            key: info.key,
+            r#type: info.r#type,
+            label: info.label,
+            ttl: info.ttl,
+            pttl: info.pttl,
+            expire_at: info.expire_at,
+            size,
+            value,
+        })
+    }
+
+    /// 模拟INFO命令中的server和keyspace信息
+    pub fn info(&self) -> serde_json::Value {
+        let mut info: HashMap<String, String> = self
+            .rdb
+            .aux
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        if let Some(version) = self.rdb.aux.get("redis-ver") {
+            info.insert("redis_version".to_string(), version.clone());
+        }
+        info.insert("rdb_version".to_string(), self.rdb.version.to_string());
+        info.insert("rdb_path".to_string(), self.config.path.clone());
+
+        for summary in self.summary().dbs {
+            info.insert(
+                format!("db{}", summary.db),
+                format!(
+                    "keys={},expires={},avg_ttl=0",
+                    summary.keys, summary.expires
+                ),
+            );
+        }
+
+        json!(info)
+    }
+
+    fn key_memory(&self, db: u32, key: &str, entry: &RdbEntry) -> KeyMemory {
+        KeyMemory {
+            db,
+            key: key.to_string(),
+            r#type: entry.value.type_name().to_string(),
+            encoding: Some(entry.encoding.to_string()),
+            bytes: memory::estimate(key, entry),
+            elements: entry.value.len() as u64,
+            ttl: self.pttl(entry),
+        }
+    }
+
+    /// 遍历键, db为空时遍历所有数据库
+    fn entries(&self, db: Option<u32>) -> impl Iterator<Item = (u32, &String, &RdbEntry)> {
+        self.rdb
+            .dbs
+            .iter()
+            .filter(move |(index, _)| db.map_or(true, |db| db == **index))
+            .flat_map(|(index, keys)| keys.iter().map(move |(key, entry)| (*index, key, entry)))
+    }
+
+    pub fn memory_report(&self, options: &MemoryReportOptions) -> MemoryReport {
+        let mut aggregator = MemoryAggregator::new(
+            &self.config.split,
+            options.depth.unwrap_or(1),
+            options.top.unwrap_or(DEFAULT_TOP),
+        );
+        for (db, key, entry) in self.entries(options.db) {
+            aggregator.add(self.key_memory(db, key, entry));
+        }
+
+        aggregator.report()
+    }
+
+    /// 导出与redis-rdb-tools格式一致的内存报告
+    pub fn write_memory_csv<W: Write>(&self, writer: W, db: Option<u32>) -> Result<u64> {
+        let mut writer = csv::Writer::from_writer(writer);
+        writer.write_record([
+            "database",
+            "type",
+            "key",
+            "size_in_bytes",
+            "encoding",
+            "num_elements",
+            "len_largest_element",
+            "expiry",
+        ])?;
+
+        let mut count = 0;
+        for (db, key, entry) in self.entries(db) {
+            let memory = self.key_memory(db, key, entry);
+            let expiry = entry
+                .expire_at
+                .and_then(chrono::NaiveDateTime::from_timestamp_millis)
+                .map(|time| time.format("%Y-%m-%dT%H:%M:%S%.6f").to_string())
+                .unwrap_or_default();
+            writer.write_record([
+                db.to_string(),
+                memory.r#type,
+                memory.key,
+                memory.bytes.to_string(),
+                entry.encoding.to_string(),
+                memory.elements.to_string(),
+                largest_element(&entry.value).to_string(),
+                expiry,
+            ])?;
+            count += 1;
+        }
+        writer.flush()?;
+
+        Ok(count)
+    }
+
+    pub fn summary(&self) -> RdbSummary {
+        RdbSummary {
+            version: self.rdb.version,
+            aux: self.rdb.aux.clone(),
+            dbs: self
+                .rdb
+                .dbs
+                .iter()
+                .map(|(db, keys)| RdbDbSummary {
+                    db: *db,
+                    keys: keys.len(),
+                    expires: keys.values().filter(|e| e.expire_at.is_some()).count(),
+                })
+                .collect(),
+            skipped: self.rdb.skipped,
+            collisions: self.rdb.collisions,
+        }
+    }
+}
+
+/// 最大元素的字节数, hash为字段和值中较长者
+fn largest_element(value: &KeyValue) -> usize {
+    match value {
+        KeyValue::String(value) => value.len(),
+        KeyValue::List(values) | KeyValue::Set(values) => {
+            values.iter().map(String::len).max().unwrap_or(0)
+        }
+        KeyValue::ZSet(values) => values.iter().map(|z| z.member.len()).max().unwrap_or(0),
+        KeyValue::Hash(values) => values
+            .iter()
+            .map(|(k, v)| k.len().max(v.len()))
+            .max()
+            .unwrap_or(0),
+        KeyValue::Stream(entries) => entries
+            .iter()
+            .flat_map(|entry| entry.fields.iter())
+            .map(|(k, v)| k.len().max(v.len()))
+            .max()
+            .unwrap_or(0),
+    }
+}
+
+/// 与在线连接的SSCAN/HSCAN/ZSCAN MATCH一致, 只保留匹配的成员或字段
+fn filter_value(value: &mut RedisValue, pattern: &str) {
+    match value {
+        RedisValue::Set(values) => values.retain(|v| glob_match(pattern, v)),
+        RedisValue::ZSet(values) => values.retain(|z| glob_match(pattern, &z.member)),
+        RedisValue::Hash(values) => values.retain(|h| glob_match(pattern, &h.key)),
+        _ => {}
+    }
+}
+
+/// 转换为get_key_detail返回的格式
+fn detail_value(value: &KeyValue) -> (usize, RedisValue) {
+    match value {
+        KeyValue::String(value) => (value.len(), RedisValue::String(value.clone())),
+        KeyValue::List(values) => (values.len(), RedisValue::List(values.clone())),
+        KeyValue::Set(values) => (values.len(), RedisValue::Set(values.clone())),
+        KeyValue::ZSet(values) => (values.len(), RedisValue::ZSet(values.clone())),
+        KeyValue::Hash(values) => (
+            values.len(),
+            RedisValue::Hash(
+                values
+                    .iter()
+                    .map(|(k, v)| HashResult::new(k.clone(), v.clone()))
+                    .collect(),
+            ),
+        ),
+        KeyValue::Stream(entries) => (
+            entries.len(),
+            RedisValue::Stream(
+                entries
+                    .iter()
+                    .rev()
+                    .take(STREAM_DETAIL_COUNT)
+                    .map(|entry| {
+                        StreamResult::new(entry.id.clone(), json!(entry.fields).to_string())
+                    })
+                    .collect(),
+            ),
+        ),
+    }
+}
+
+/// 打开本地rdb文件作为只读的离线连接
+#[tauri::command]
+#[instrument(skip(offline))]
+pub async fn open_rdb(
+    offline: State<'_, OfflineState>,
+    config: OfflineConfig,
+) -> Result<RdbSummary> {
+    let path = config.path.clone();
+    let rdb = tauri::async_runtime::spawn_blocking(move || RdbFile::open(&path)).await??;
+
+    let instance = OfflineInstance { config, rdb };
+    let summary = instance.summary();
+    offline
+        .0
+        .write()
+        .unwrap()
+        .insert(instance.config.id.clone(), Arc::new(instance));
+
+    info!(?summary, "打开rdb文件成功");
+    Ok(summary)
+}
+
+/// 关闭离线连接
+#[tauri::command]
+#[instrument(skip(offline))]
+pub async fn close_rdb(offline: State<'_, OfflineState>, id: String) -> Result<()> {
+    offline.0.write().unwrap().remove(&id);
+
+    info!(id, "关闭rdb文件成功");
+    Ok(())
+}
+
+fn get_instance(offline: &OfflineState, id: &str) -> Result<Arc<OfflineInstance>> {
+    offline
+        .get(id)
+        .ok_or_else(|| format!("离线连接不存在: {id}").into())
+}
+
+/// 分析rdb文件的内存占用, 按类型, 编码和命名空间汇总并列出最大的键
+#[tauri::command]
+#[instrument(skip(offline))]
+pub async fn rdb_memory_report(
+    offline: State<'_, OfflineState>,
+    id: String,
+    options: MemoryReportOptions,
+) -> Result<MemoryReport> {
+    let instance = get_instance(&offline, &id)?;
+    let report =
+        tauri::async_runtime::spawn_blocking(move || instance.memory_report(&options)).await?;
+
+    info!(keys = report.keys, bytes = report.bytes, "分析rdb内存成功");
+    Ok(report)
+}
+
+/// 导出rdb文件中每个键的内存占用到csv文件
+#[tauri::command]
+#[instrument(skip(offline))]
+pub async fn export_rdb_memory_report(
+    offline: State<'_, OfflineState>,
+    id: String,
+    db: Option<u32>,
+    path: String,
+) -> Result<u64> {
+    let instance = get_instance(&offline, &id)?;
+    let count = tauri::async_runtime::spawn_blocking(move || {
+        let file = File::create(&path)?;
+        instance.write_memory_csv(BufWriter::new(file), db)
+    })
+    .await??;
+
+    info!(count, "导出rdb内存报告成功");
+    Ok(count)
+}
@@ -0,0 +1,204 @@
+use crate::{
+    error::Result,
+    get_cluster_clients,
+    model::{EvalRequest, EvalResult, RespValue, SavedScript},
+    script::ScriptStore,
+    slot::key_slot,
+    CmdLog, History, RedisState,
+};
+use redis::{Cmd, ErrorKind, Value};
+use std::time::Instant;
+use tauri::State;
+use tracing::{info, instrument};
+
+use super::{query_all_masters, select_db};
+
+/// 执行lua脚本, KEYS和ARGV分别传入, 返回带类型的结果
+///
+/// 传入脚本时先使用EVALSHA, 服务端没有缓存时再使用EVAL; 集群模式下按KEYS所在的槽路由
+#[tauri::command]
+#[instrument(skip(state, history, request), fields(keys = ?request.keys))]
+pub async fn eval_script(
+    state: State<'_, RedisState>,
+    history: State<'_, History>,
+    id: String,
+    db: u8,
+    request: EvalRequest,
+) -> Result<EvalResult> {
+    let sha = match (&request.script, &request.sha) {
+        (Some(script), _) => redis::Script::new(script).get_hash().to_string(),
+        (None, Some(sha)) => sha.clone(),
+        (None, None) => return Err("脚本和sha不能都为空".into()),
+    };
+
+    let mut redis_state = state.0.lock().await;
+    let (con, config) = redis_state.get_con_and_config(&id)?;
+    select_db(config, db, con, &history).await?;
+
+    if config.cluster {
+        check_slot(&request.keys)?;
+    }
+
+    let start = Instant::now();
+    let mut res: redis::RedisResult<Value> = eval_cmd("EVALSHA", &sha, &request)
+        .log(history.0.clone(), config)
+        .query_async(con)
+        .await;
+    if let (Err(err), Some(script)) = (&res, &request.script) {
+        if err.kind() == ErrorKind::NoScriptError {
+            res = eval_cmd("EVAL", script, &request)
+                .log(history.0.clone(), config)
+                .query_async(con)
+                .await;
+        }
+    }
+    let elapsed = start.elapsed().as_millis() as u64;
+    let result = RespValue::from_result(res)?;
+
+    info!(sha, elapsed, "执行脚本成功");
+    Ok(EvalResult {
+        sha,
+        result,
+        elapsed,
+    })
+}
+
+/// 缓存脚本, 返回sha1; 集群模式下缓存到所有主节点
+#[tauri::command]
+#[instrument(skip(state, history, script))]
+pub async fn script_load(
+    state: State<'_, RedisState>,
+    history: State<'_, History>,
+    id: String,
+    script: String,
+) -> Result<String> {
+    let mut redis_state = state.0.lock().await;
+    let (con, config) = redis_state.get_con_and_config(&id)?;
+
+    let mut cmd = redis::cmd("SCRIPT");
+    cmd.arg("LOAD").arg(&script).log(history.0.clone(), config);
+
+    let sha: String = query_all_masters(config, con, &cmd).await?;
+
+    info!(sha, "缓存脚本成功");
+    Ok(sha)
+}
+
+/// 查询脚本是否已缓存; 集群模式下所有主节点都缓存时才返回true
+#[tauri::command]
+#[instrument(skip(state, history))]
+pub async fn script_exists(
+    state: State<'_, RedisState>,
+    history: State<'_, History>,
+    id: String,
+    shas: Vec<String>,
+) -> Result<Vec<bool>> {
+    if shas.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut redis_state = state.0.lock().await;
+    let (con, config) = redis_state.get_con_and_config(&id)?;
+
+    let mut cmd = redis::cmd("SCRIPT");
+    cmd.arg("EXISTS").arg(&shas).log(history.0.clone(), config);
+
+    let exists: Vec<bool> = if config.cluster {
+        let mut exists = vec![true; shas.len()];
+        for client in get_cluster_clients(config, con).await? {
+            let mut node = client.get_async_connection().await?;
+            let node_exists: Vec<bool> = cmd.query_async(&mut node).await?;
+            for (exists, node_exists) in exists.iter_mut().zip(node_exists) {
+                *exists &= node_exists;
+            }
+        }
+        exists
+    } else {
+        cmd.query_async(con).await?
+    };
+
+    Ok(exists)
+}
+
+/// 清空脚本缓存, mode为ASYNC或SYNC(redis 6.2+); 集群模式下清空所有主节点
+#[tauri::command]
+#[instrument(skip(state, history))]
+pub async fn script_flush(
+    state: State<'_, RedisState>,
+    history: State<'_, History>,
+    id: String,
+    mode: Option<String>,
+) -> Result<()> {
+    let mut redis_state = state.0.lock().await;
+    let (con, config) = redis_state.get_con_and_config(&id)?;
+
+    let mut cmd = redis::cmd("SCRIPT");
+    cmd.arg("FLUSH");
+    if let Some(mode) = mode {
+        let mode = mode.to_uppercase();
+        if mode != "ASYNC" && mode != "SYNC" {
+            return Err(format!("不支持的模式: {mode}").into());
+        }
+        cmd.arg(mode);
+    }
+    cmd.log(history.0.clone(), config);
+
+    query_all_masters::<()>(config, con, &cmd).await?;
+
+    info!("清空脚本缓存成功");
+    Ok(())
+}
+
+/// 连接保存的脚本
+#[tauri::command]
+#[instrument(skip(scripts))]
+pub async fn list_scripts(scripts: State<'_, ScriptStore>, id: String) -> Result<Vec<SavedScript>> {
+    Ok(scripts.list(&id))
+}
+
+/// 保存脚本, id为0时新建
+#[tauri::command]
+#[instrument(skip(scripts, script), fields(name = script.name))]
+pub async fn save_script(
+    scripts: State<'_, ScriptStore>,
+    id: String,
+    script: SavedScript,
+) -> Result<SavedScript> {
+    let script = scripts.save(&id, script)?;
+
+    info!(script_id = script.id, "保存脚本成功");
+    Ok(script)
+}
+
+#[tauri::command]
+#[instrument(skip(scripts))]
+pub async fn delete_script(
+    scripts: State<'_, ScriptStore>,
+    id: String,
+    script_id: u64,
+) -> Result<bool> {
+    scripts.delete(&id, script_id)
+}
+
+/// EVAL script numkeys key [key ...] arg [arg ...]
+///
+/// 集群连接按第一个key所在的槽路由, 没有key时发送到随机节点
+fn eval_cmd(name: &str, script: &str, request: &EvalRequest) -> Cmd {
+    let mut cmd = redis::cmd(name);
+    cmd.arg(script)
+        .arg(request.keys.len())
+        .arg(&request.keys)
+        .arg(&request.args);
+    cmd
+}
+
+/// 集群模式下脚本只能访问同一个槽中的键
+pub(crate) fn check_slot(keys: &[String]) -> Result<()> {
+    let mut slots = keys.iter().map(|key| key_slot(key.as_bytes()));
+    if let Some(first) = slots.next() {
+        if slots.any(|slot| slot != first) {
+            return Err("集群模式下所有KEYS必须属于同一个槽, 可以使用{hashtag}".into());
+        }
+    }
+    Ok(())
+}
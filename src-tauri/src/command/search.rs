@@ -0,0 +1,232 @@
+use crate::{
+    analysis::DEFAULT_DELAY,
+    config::RedisConfig,
+    error::Result,
+    job::{spawn_job, JobContext, Jobs},
+    model::{KeyRecord, KeyValue, MatchLocation, SearchOptions, SearchProgress, ValueMatch},
+    scan::{KeyScanner, SCAN_COUNT},
+    value::read_record,
+    History, RedisState, SharedConnection,
+};
+use futures::{stream, StreamExt};
+use regex::{Regex, RegexBuilder};
+use std::time::Duration;
+use tauri::{State, Window};
+use tracing::{info, instrument};
+
+/// 默认最多返回的匹配数量
+const DEFAULT_MAX_MATCHES: usize = 1000;
+/// 同时读取的键数量
+const CONCURRENCY: usize = 8;
+/// 匹配内容最多返回的字符数
+const MAX_VALUE_CHARS: usize = 200;
+
+/// 在后台搜索值中包含指定内容的键, 匹配项通过任务数据事件实时返回, 返回任务id
+#[tauri::command]
+#[instrument(skip(window, state, history, jobs))]
+pub async fn search_values(
+    window: Window,
+    state: State<'_, RedisState>,
+    history: State<'_, History>,
+    jobs: State<'_, Jobs>,
+    id: String,
+    db: u8,
+    options: SearchOptions,
+) -> Result<String> {
+    let matcher = Matcher::new(&options)?;
+    let config = state.0.lock().await.get_config(&id)?;
+    let con = SharedConnection::open(&config, db).await?;
+
+    let pattern = options.pattern.as_deref().unwrap_or("*");
+    history.add_log(
+        format!("search db{db} {pattern} {:?}", options.query),
+        &config,
+    );
+
+    let job_id = spawn_job(window, &jobs, "search", |ctx| {
+        search(ctx, config, con, matcher, options)
+    });
+
+    info!(job_id, "开始搜索");
+    Ok(job_id)
+}
+
+async fn search(
+    ctx: JobContext,
+    config: RedisConfig,
+    con: SharedConnection,
+    matcher: Matcher,
+    options: SearchOptions,
+) -> Result<SearchProgress> {
+    let delay = Duration::from_millis(options.delay.unwrap_or(DEFAULT_DELAY));
+    let pattern = options.pattern.as_deref().unwrap_or("*");
+    let scan_count = options.scan_count.unwrap_or(SCAN_COUNT).max(1);
+    let max_matches = options.max_matches.unwrap_or(DEFAULT_MAX_MATCHES).max(1) as u64;
+
+    let mut progress = SearchProgress::default();
+    let mut scanner = KeyScanner::new(&config, &con, pattern, scan_count).await?;
+    while let Some((node, keys)) = scanner.next_batch().await? {
+        if ctx.is_cancelled() {
+            break;
+        }
+
+        progress.scanned += keys.len() as u64;
+        let records: Vec<_> = stream::iter(keys)
+            .map(|key| {
+                let mut con = node.clone();
+                async move { read_record(&mut con, &key).await }
+            })
+            .buffered(CONCURRENCY)
+            .collect()
+            .await;
+
+        let mut matches = vec![];
+        for record in records {
+            match record {
+                Ok(Some(record)) => {
+                    let found = find_matches(&matcher, &record);
+                    if !found.is_empty() {
+                        progress.keys += 1;
+                        matches.extend(found);
+                    }
+                }
+                // 扫描后已被删除
+                Ok(None) => {}
+                Err(_) => progress.failed += 1,
+            }
+        }
+
+        // 恰好达到上限时继续扫描, 只有确实还有更多匹配时才标记为截断
+        let remaining = (max_matches - progress.matches) as usize;
+        if matches.len() > remaining {
+            matches.truncate(remaining);
+            progress.truncated = true;
+        }
+        progress.matches += matches.len() as u64;
+        if !matches.is_empty() {
+            ctx.send(matches);
+        }
+        ctx.progress(&progress);
+
+        if progress.truncated {
+            break;
+        }
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    info!(job_id = ctx.id(), ?progress, "搜索完成");
+    Ok(progress)
+}
+
+enum Matcher {
+    Substring { needle: String, ignore_case: bool },
+    Regex(Regex),
+}
+
+impl Matcher {
+    fn new(options: &SearchOptions) -> Result<Matcher> {
+        if options.query.is_empty() {
+            return Err("搜索内容不能为空".into());
+        }
+
+        if options.regex {
+            let regex = RegexBuilder::new(&options.query)
+                .case_insensitive(options.ignore_case)
+                .build()
+                .map_err(|err| format!("正则表达式错误: {err}"))?;
+            return Ok(Matcher::Regex(regex));
+        }
+
+        let needle = if options.ignore_case {
+            options.query.to_lowercase()
+        } else {
+            options.query.clone()
+        };
+        Ok(Matcher::Substring {
+            needle,
+            ignore_case: options.ignore_case,
+        })
+    }
+
+    fn is_match(&self, value: &str) -> bool {
+        match self {
+            Matcher::Substring {
+                needle,
+                ignore_case: true,
+            } => value.to_lowercase().contains(needle.as_str()),
+            Matcher::Substring { needle, .. } => value.contains(needle.as_str()),
+            Matcher::Regex(regex) => regex.is_match(value),
+        }
+    }
+}
+
+/// 查找值中所有匹配的元素
+fn find_matches(matcher: &Matcher, record: &KeyRecord) -> Vec<ValueMatch> {
+    let mut matches = vec![];
+    let mut check = |value: &str, location: MatchLocation| {
+        if matcher.is_match(value) {
+            matches.push(ValueMatch {
+                key: record.key.clone(),
+                r#type: record.value.type_name().to_string(),
+                location,
+                value: value.chars().take(MAX_VALUE_CHARS).collect(),
+            });
+        }
+    };
+
+    match &record.value {
+        KeyValue::String(value) => check(value, MatchLocation::Value),
+        KeyValue::List(values) => {
+            for (index, value) in values.iter().enumerate() {
+                check(value, MatchLocation::Index { index });
+            }
+        }
+        KeyValue::Set(values) => {
+            for value in values {
+                check(value, MatchLocation::Member { score: None });
+            }
+        }
+        KeyValue::ZSet(values) => {
+            for z in values {
+                check(
+                    &z.member,
+                    MatchLocation::Member {
+                        score: Some(z.score),
+                    },
+                );
+            }
+        }
+        KeyValue::Hash(values) => {
+            for (field, value) in values {
+                check(
+                    field,
+                    MatchLocation::Field {
+                        field: field.clone(),
+                    },
+                );
+                check(
+                    value,
+                    MatchLocation::HashValue {
+                        field: field.clone(),
+                    },
+                );
+            }
+        }
+        KeyValue::Stream(entries) => {
+            for entry in entries {
+                for (field, value) in &entry.fields {
+                    let location = || MatchLocation::Entry {
+                        id: entry.id.clone(),
+                        field: field.clone(),
+                    };
+                    check(field, location());
+                    check(value, location());
+                }
+            }
+        }
+    }
+
+    matches
+}
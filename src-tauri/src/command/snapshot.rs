@@ -0,0 +1,124 @@
+use crate::{
+    diff::compare_values,
+    error::Result,
+    model::{KeyRecord, Snapshot, SnapshotSummary, ValueComparison, VersionComparison},
+    snapshot::SnapshotStore,
+    value::{read_record, record_cmds},
+    CmdLog, History, RedisState,
+};
+use redis::AsyncCommands;
+use tauri::State;
+use tracing::{info, instrument};
+
+use super::select_db;
+
+/// 键的历史版本, 最新的在前
+#[tauri::command]
+#[instrument(skip(snapshots))]
+pub async fn list_key_versions(
+    snapshots: State<'_, SnapshotStore>,
+    id: String,
+    db: u8,
+    key: String,
+) -> Result<Vec<SnapshotSummary>> {
+    Ok(snapshots.list(&id, db, &key))
+}
+
+/// 查看键的某个历史版本
+#[tauri::command]
+#[instrument(skip(snapshots))]
+pub async fn get_key_version(
+    snapshots: State<'_, SnapshotStore>,
+    id: String,
+    version: u64,
+) -> Result<Snapshot> {
+    find_version(&snapshots, &id, version)
+}
+
+/// 比较历史版本与键的当前值, key为空时使用版本中的键
+#[tauri::command]
+#[instrument(skip(state, snapshots, history))]
+pub async fn diff_key_version(
+    state: State<'_, RedisState>,
+    snapshots: State<'_, SnapshotStore>,
+    history: State<'_, History>,
+    id: String,
+    version: u64,
+    key: Option<String>,
+) -> Result<VersionComparison> {
+    let version = find_version(&snapshots, &id, version)?;
+    let key = key.unwrap_or_else(|| version.record.key.clone());
+
+    let mut redis_state = state.0.lock().await;
+    let (con, config) = redis_state.get_con_and_config(&id)?;
+    select_db(config, version.db, con, &history).await?;
+
+    let current = read_record(con, &key).await?;
+
+    let diff = match &current {
+        Some(current) => compare_values(&version.record.value, &current.value),
+        None => ValueComparison::Missing,
+    };
+    let equal = diff.is_equal();
+
+    info!(equal, "比较历史版本成功");
+    Ok(VersionComparison {
+        version,
+        current,
+        equal,
+        diff,
+    })
+}
+
+/// 将键恢复为历史版本的值, key为空时使用版本中的键
+///
+/// 恢复前的值会保存为新版本; 保留键当前的过期时间, 键不存在时不设置过期时间
+#[tauri::command]
+#[instrument(skip(state, snapshots, history))]
+pub async fn restore_key_version(
+    state: State<'_, RedisState>,
+    snapshots: State<'_, SnapshotStore>,
+    history: State<'_, History>,
+    id: String,
+    version: u64,
+    key: Option<String>,
+) -> Result<()> {
+    let version = find_version(&snapshots, &id, version)?;
+    let key = key.unwrap_or_else(|| version.record.key.clone());
+
+    let mut redis_state = state.0.lock().await;
+    let (con, config) = redis_state.get_con_and_config(&id)?;
+    select_db(config, version.db, con, &history).await?;
+
+    snapshots
+        .capture(con, &id, version.db, "restore", &key)
+        .await;
+
+    let pttl: i64 = con.pttl(&key).await?;
+    let record = KeyRecord {
+        key,
+        pttl: pttl.max(-1),
+        value: version.record.value,
+    };
+
+    let mut pipe = redis::pipe();
+    // 集群模式下的事务没有键, 无法路由到对应节点; 所有命令属于同一个键, 按顺序执行即可
+    if !config.cluster {
+        pipe.atomic();
+    }
+    for cmd in record_cmds(&record) {
+        pipe.add_command(cmd).ignore();
+    }
+    pipe.log(history.0.clone(), config)
+        .query_async::<_, ()>(con)
+        .await?;
+
+    info!(key = record.key, "恢复历史版本成功");
+    Ok(())
+}
+
+fn find_version(snapshots: &SnapshotStore, id: &str, version: u64) -> Result<Snapshot> {
+    snapshots
+        .get(id, version)
+        .ok_or_else(|| format!("历史版本不存在: {version}").into())
+}
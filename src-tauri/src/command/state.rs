@@ -1,6 +1,6 @@
-use crate::{config::RedisConfig, error::Result};
+use crate::{config::RedisConfig, error::Result, get_cluster_clients, OfflineInstance};
 use anyhow::Context;
-use redis::{aio::ConnectionLike, Cmd, Pipeline};
+use redis::{aio::ConnectionLike, Cmd, IntoConnectionInfo, Pipeline};
 use std::{collections::HashMap, fmt::Debug, sync::Arc};
 use tauri::async_runtime::Mutex;
 
@@ -67,6 +67,11 @@ impl Redis {
         let instance = self.redis_instances.get_mut(id).context("客户端未连接")?;
         Ok((&mut instance.con, &instance.config))
     }
+
+    pub fn get_config(&self, id: &str) -> Result<RedisConfig> {
+        let instance = self.redis_instances.get(id).context("客户端未连接")?;
+        Ok(instance.config.clone())
+    }
 }
 
 pub enum RedisConnection {
@@ -116,9 +121,93 @@ impl ConnectionLike for RedisConnection {
     }
 }
 
+/// 后台任务使用的独立连接, 不占用浏览键时的共享连接, 可克隆后并发使用
+#[derive(Clone)]
+pub enum SharedConnection {
+    Connection(redis::aio::MultiplexedConnection),
+    ClusterConnection(redis::cluster_async::ClusterConnection),
+}
+
+impl SharedConnection {
+    /// 打开连接并选择数据库, 集群模式下忽略数据库
+    pub async fn open(config: &RedisConfig, db: u8) -> Result<SharedConnection> {
+        if config.cluster {
+            let client = redis::cluster::ClusterClient::new(vec![config.clone()])?;
+            let con = client.get_async_connection().await?;
+            Ok(SharedConnection::ClusterConnection(con))
+        } else {
+            let mut connection_info = config.clone().into_connection_info()?;
+            connection_info.redis.db = db as i64;
+            let client = redis::Client::open(connection_info)?;
+            let con = client.get_multiplexed_tokio_connection().await?;
+            Ok(SharedConnection::Connection(con))
+        }
+    }
+
+    /// 获取所有主节点的连接, 单机模式下返回自身
+    ///
+    /// 同一节点上的键可以放在一个pipeline中执行, 集群连接只能按槽路由
+    pub async fn nodes(&self, config: &RedisConfig) -> Result<Vec<SharedConnection>> {
+        let SharedConnection::ClusterConnection(con) = self else {
+            return Ok(vec![self.clone()]);
+        };
+
+        let clients = get_cluster_clients(config, &mut con.clone()).await?;
+        let mut nodes = vec![];
+        for client in clients {
+            let con = client.get_multiplexed_tokio_connection().await?;
+            nodes.push(SharedConnection::Connection(con));
+        }
+
+        Ok(nodes)
+    }
+}
+
+impl ConnectionLike for SharedConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> redis::RedisFuture<'a, redis::Value> {
+        match self {
+            SharedConnection::Connection(con) => con.req_packed_command(cmd),
+            SharedConnection::ClusterConnection(con) => con.req_packed_command(cmd),
+        }
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a redis::Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> redis::RedisFuture<'a, Vec<redis::Value>> {
+        match self {
+            SharedConnection::Connection(con) => con.req_packed_commands(cmd, offset, count),
+            SharedConnection::ClusterConnection(con) => con.req_packed_commands(cmd, offset, count),
+        }
+    }
+
+    fn get_db(&self) -> i64 {
+        match self {
+            SharedConnection::Connection(con) => con.get_db(),
+            SharedConnection::ClusterConnection(con) => con.get_db(),
+        }
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct RedisState(pub Arc<Mutex<Redis>>);
 
+/// 已打开的离线连接
+#[derive(Debug, Default)]
+pub struct OfflineState(pub std::sync::RwLock<HashMap<String, Arc<OfflineInstance>>>);
+
+impl OfflineState {
+    pub fn get(&self, id: &str) -> Option<Arc<OfflineInstance>> {
+        self.0.read().unwrap().get(id).cloned()
+    }
+
+    pub fn is_offline(&self, id: &str) -> bool {
+        self.0.read().unwrap().contains_key(id)
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct History(pub Arc<std::sync::Mutex<Vec<String>>>);
 
@@ -1,65 +1,85 @@
-use serde_json::json;
+use anyhow::Context;
 use tauri::State;
-use tracing::{info, instrument};
+use tracing::{info, instrument, warn};
 
-use crate::{error::Result, select_db, CmdLog, History, RedisState};
+use crate::{
+    cli_args::split_args,
+    error::Result,
+    model::{RespValue, TerminalReply, TerminalSessionInfo},
+    terminal::{TerminalSession, TerminalState},
+    History, RedisState,
+};
 
-/// 在终端执行执行
+/// 为终端标签页打开独立的连接会话
 #[tauri::command]
-#[instrument(skip(state, history))]
-pub async fn terminal(
+#[instrument(skip(state, history, terminals))]
+pub async fn terminal_open(
     state: State<'_, RedisState>,
     history: State<'_, History>,
+    terminals: State<'_, TerminalState>,
     id: String,
     db: u8,
-    args: Option<Vec<String>>,
-) -> Result<serde_json::Value> {
-    info!(?args);
+) -> Result<TerminalSessionInfo> {
+    let config = state.0.lock().await.get_config(&id)?;
+    let session = TerminalSession::open(&config, db, &history).await?;
+    let info = terminals.insert(session);
 
-    let mut client = state.0.lock().await;
-    let (con, config) = client.get_con_and_config(&id)?;
-    select_db(config, db, con, &history).await?;
+    info!(session_id = info.session_id, "打开终端会话成功");
+    Ok(info)
+}
 
-    let Some(args) = args else {
-        return Ok(json!(""));
-    };
+/// 在终端会话中执行命令, 返回带类型的结果和会话状态, 服务端错误也作为返回值
+///
+/// line为输入的整行命令, 按redis-cli的规则拆分参数; 没有line时使用已拆分的args
+#[tauri::command]
+#[instrument(skip(history, terminals))]
+pub async fn terminal(
+    history: State<'_, History>,
+    terminals: State<'_, TerminalState>,
+    session_id: String,
+    line: Option<String>,
+    args: Option<Vec<String>>,
+) -> Result<TerminalReply> {
+    let session = terminals.get(&session_id).context("终端会话不存在")?;
+    let mut session = session.lock().await;
 
+    let args = match line {
+        Some(line) => split_args(&line)?,
+        None => args
+            .unwrap_or_default()
+            .into_iter()
+            .map(String::into_bytes)
+            .collect(),
+    };
     if args.is_empty() {
-        return Ok(json!(""));
+        return Ok(TerminalReply {
+            result: RespValue::Nil,
+            session: session.info().clone(),
+        });
     }
 
-    let cmd_name = &args[0];
-    let args = if args.len() == 1 {
-        vec![]
-    } else {
-        args[1..].to_vec()
-    };
-
-    let res: redis::Value = redis::cmd(cmd_name.as_ref())
-        .arg(args)
-        .log(history.0.clone(), config)
-        .query_async(con)
-        .await?;
-
-    let json_result = parse_result(res);
-
-    Ok(json_result)
-}
-
-/// 解析redis命令返回的结果
-fn parse_result(res: redis::Value) -> serde_json::Value {
-    match res {
-        redis::Value::Nil => json!(""),
-        redis::Value::Int(val) => json!(val),
-        redis::Value::Data(ref data) => json!(String::from_utf8_lossy(data)),
-        redis::Value::Bulk(data) => {
-            let mut result = vec![];
-            for val in data {
-                result.push(json!(parse_result(val)));
-            }
-            json!(result)
+    match session.execute(args, &history).await {
+        Ok(result) => Ok(TerminalReply {
+            result,
+            session: session.info().clone(),
+        }),
+        Err(err) => {
+            terminals.remove(&session_id);
+            warn!(session_id, error = err.message(), "终端连接断开");
+            Err(err)
         }
-        redis::Value::Status(status) => json!(status),
-        redis::Value::Okay => json!("Ok"),
     }
 }
+
+/// 关闭终端会话, 连接随之断开
+#[tauri::command]
+#[instrument(skip(terminals))]
+pub async fn terminal_close(
+    terminals: State<'_, TerminalState>,
+    session_id: String,
+) -> Result<bool> {
+    let closed = terminals.remove(&session_id);
+
+    info!(session_id, closed, "关闭终端会话");
+    Ok(closed)
+}
@@ -0,0 +1,160 @@
+use crate::{
+    analysis::{
+        add_group, namespace, sorted_groups, ttl_bucket, ttl_groups, DISTRIBUTION_TTL_BUCKETS,
+    },
+    error::Result,
+    model::{ExpiryForecast, TtlOptions, TtlReport},
+    scan::{KeyScanner, SCAN_COUNT},
+    History, RedisState, SharedConnection,
+};
+use std::collections::HashMap;
+use tauri::State;
+use tracing::{info, instrument};
+
+use super::is_unsupported_error;
+
+/// 默认采样的键数量
+const DEFAULT_SAMPLE: usize = 10_000;
+/// 默认预测未来24小时
+const DEFAULT_HOURS: u32 = 24;
+const MAX_HOURS: u32 = 24 * 30;
+const HOUR: i64 = 3_600_000;
+
+/// 采样数据库中的键, 统计剩余过期时间的分布, 永不过期的键以及未来将要过期的键
+#[tauri::command]
+#[instrument(skip(state, history))]
+pub async fn ttl_distribution(
+    state: State<'_, RedisState>,
+    history: State<'_, History>,
+    id: String,
+    db: u8,
+    options: TtlOptions,
+) -> Result<TtlReport> {
+    let config = state.0.lock().await.get_config(&id)?;
+    let con = SharedConnection::open(&config, db).await?;
+    history.add_log(format!("ttl distribution db{db}"), &config);
+
+    let sample = options.sample.unwrap_or(DEFAULT_SAMPLE).max(1) as u64;
+    let hours = options.hours.unwrap_or(DEFAULT_HOURS).clamp(1, MAX_HOURS);
+    let depth = options.depth.unwrap_or(1);
+
+    let mut nodes = vec![];
+    let mut total = 0;
+    for mut node in con.nodes(&config).await? {
+        let size: u64 = redis::cmd("DBSIZE").query_async(&mut node).await?;
+        total += size;
+        nodes.push((node, size));
+    }
+
+    let mut memory = true;
+    let mut sampled = 0;
+    let mut buckets = HashMap::new();
+    let mut no_ttl = HashMap::new();
+    // 每小时内过期的键数量和占用内存
+    let mut expiring = vec![(0, 0); hours as usize];
+
+    for (node, size) in nodes {
+        // 集群模式下按节点的键数量分配采样数量
+        let quota = (sample * size + total.saturating_sub(1))
+            .checked_div(total)
+            .unwrap_or(0) as usize;
+
+        let mut taken = 0;
+        let mut scanner = KeyScanner::new(&config, &node, "*", SCAN_COUNT).await?;
+        while taken < quota {
+            let Some((mut node, mut keys)) = scanner.next_batch().await? else {
+                break;
+            };
+            keys.truncate(quota - taken);
+            taken += keys.len();
+
+            let values = sample_keys(&mut node, &keys, &mut memory).await?;
+            for (key, (pttl, bytes)) in keys.iter().zip(values) {
+                // 扫描后已被删除或过期
+                if pttl == -2 {
+                    continue;
+                }
+
+                sampled += 1;
+                add_group(
+                    &mut buckets,
+                    ttl_bucket(DISTRIBUTION_TTL_BUCKETS, pttl),
+                    bytes,
+                    0,
+                );
+                if pttl < 0 {
+                    let prefix = namespace(key, &config.split, depth).unwrap_or_default();
+                    add_group(&mut no_ttl, &prefix, bytes, 0);
+                } else if let Some(hour) = expiring.get_mut((pttl / HOUR) as usize) {
+                    hour.0 += 1;
+                    hour.1 += bytes;
+                }
+            }
+        }
+    }
+
+    let scale = if sampled == 0 {
+        0.0
+    } else {
+        total as f64 / sampled as f64
+    };
+    let (mut keys, mut bytes) = (0, 0);
+    let forecast = expiring
+        .into_iter()
+        .enumerate()
+        .map(|(hour, (hour_keys, hour_bytes))| {
+            keys += hour_keys;
+            bytes += hour_bytes;
+            ExpiryForecast {
+                hour: hour as u32 + 1,
+                keys: (keys as f64 * scale).round() as u64,
+                bytes: (bytes as f64 * scale).round() as u64,
+            }
+        })
+        .collect();
+
+    let report = TtlReport {
+        total,
+        sampled,
+        memory,
+        buckets: ttl_groups(DISTRIBUTION_TTL_BUCKETS, buckets),
+        no_ttl: sorted_groups(no_ttl),
+        forecast,
+    };
+
+    info!(total, sampled, "统计过期时间分布成功");
+    Ok(report)
+}
+
+/// 查询一批键的剩余过期时间和占用内存, 不支持MEMORY USAGE时内存为0
+async fn sample_keys(
+    con: &mut SharedConnection,
+    keys: &[String],
+    memory: &mut bool,
+) -> Result<Vec<(i64, u64)>> {
+    if *memory {
+        let mut pipe = redis::pipe();
+        for key in keys {
+            pipe.pttl(key).cmd("MEMORY").arg("USAGE").arg(key);
+        }
+        let res: redis::RedisResult<Vec<(i64, Option<u64>)>> = pipe.query_async(con).await;
+        match res {
+            Ok(values) => {
+                return Ok(values
+                    .into_iter()
+                    .map(|(pttl, bytes)| (pttl, bytes.unwrap_or(0)))
+                    .collect())
+            }
+            Err(err) if is_unsupported_error(&err) => *memory = false,
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    let mut pipe = redis::pipe();
+    for key in keys {
+        pipe.pttl(key);
+    }
+    let values: Vec<i64> = pipe.query_async(con).await?;
+
+    Ok(values.into_iter().map(|pttl| (pttl, 0)).collect())
+}
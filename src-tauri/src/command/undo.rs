@@ -0,0 +1,129 @@
+use crate::{
+    dump::KeyDump,
+    error::Result,
+    model::{KeyError, UndoReport, UndoSummary},
+    undo::{UndoEntry, UndoState},
+    History, RedisState, SharedConnection,
+};
+use chrono::Local;
+use futures::future::join_all;
+use redis::RedisResult;
+use tauri::State;
+use tracing::{info, instrument};
+
+/// 每批并发执行的RESTORE数量
+const RESTORE_BATCH: usize = 100;
+/// 结果中最多保留的失败记录
+const MAX_FAILURES: usize = 100;
+
+/// 列出连接的撤销记录, 最新的在前
+#[tauri::command]
+#[instrument(skip(undo))]
+pub async fn list_undo(undo: State<'_, UndoState>, id: String) -> Result<Vec<UndoSummary>> {
+    Ok(undo.list(&id))
+}
+
+/// 撤销最近一次破坏性操作
+#[tauri::command]
+#[instrument(skip(state, undo, history))]
+pub async fn undo_last(
+    state: State<'_, RedisState>,
+    undo: State<'_, UndoState>,
+    history: State<'_, History>,
+    id: String,
+) -> Result<UndoReport> {
+    let entry = undo.take_last(&id).ok_or("没有可以撤销的操作")?;
+    restore_entry(&state, &undo, &history, &id, entry).await
+}
+
+/// 恢复指定的撤销记录
+#[tauri::command]
+#[instrument(skip(state, undo, history))]
+pub async fn restore_from_undo(
+    state: State<'_, RedisState>,
+    undo: State<'_, UndoState>,
+    history: State<'_, History>,
+    id: String,
+    undo_id: u64,
+) -> Result<UndoReport> {
+    let entry = undo
+        .take(&id, undo_id)
+        .ok_or_else(|| format!("撤销记录不存在: {undo_id}"))?;
+    restore_entry(&state, &undo, &history, &id, entry).await
+}
+
+async fn restore_entry(
+    state: &RedisState,
+    undo: &UndoState,
+    history: &History,
+    id: &str,
+    entry: UndoEntry,
+) -> Result<UndoReport> {
+    let config = state.0.lock().await.get_config(id)?;
+    let con = match SharedConnection::open(&config, entry.db).await {
+        Ok(con) => con,
+        Err(err) => {
+            undo.put_back(id, entry);
+            return Err(err);
+        }
+    };
+
+    let elapsed = Local::now().timestamp_millis() - entry.created_at;
+    let mut report = UndoReport {
+        id: entry.id,
+        ..Default::default()
+    };
+
+    for batch in entry.keys.chunks(RESTORE_BATCH) {
+        let results = join_all(batch.iter().map(|(key, dump)| {
+            let mut con = con.clone();
+            async move {
+                // 按保存时的剩余过期时间扣除已经过去的时间
+                let mut pttl = dump.pttl;
+                if pttl > 0 {
+                    pttl -= elapsed;
+                    if pttl <= 0 {
+                        return Ok(false);
+                    }
+                }
+
+                let dump = KeyDump {
+                    pttl,
+                    payload: dump.payload.clone(),
+                };
+                let res: RedisResult<()> = dump.restore_cmd(key, true).query_async(&mut con).await;
+                res.map(|_| true)
+            }
+        }))
+        .await;
+
+        for ((key, _), result) in batch.iter().zip(results) {
+            match result {
+                Ok(true) => report.restored += 1,
+                Ok(false) => report.expired += 1,
+                Err(err) => {
+                    report.failed += 1;
+                    if report.failures.len() < MAX_FAILURES {
+                        report.failures.push(KeyError {
+                            key: key.clone(),
+                            message: err.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    history.add_log(
+        format!(
+            "[db{}] undo {} ({} keys)",
+            entry.db,
+            entry.op,
+            entry.keys.len()
+        ),
+        &config,
+    );
+
+    info!(?report, "撤销操作完成");
+    Ok(report)
+}
@@ -25,3 +25,13 @@ impl IntoConnectionInfo for RedisConfig {
         })
     }
 }
+
+/// 离线连接(本地rdb文件)的配置
+#[derive(Debug, Clone, Deserialize)]
+pub struct OfflineConfig {
+    pub id: String,
+    pub name: String,
+    /// rdb文件路径
+    pub path: String,
+    pub split: String,
+}
@@ -0,0 +1,341 @@
+//! lua调试器(LDB)会话, 每个会话使用独立的连接
+//!
+//! 调试模式下服务端对每条调试命令回复一组日志行, 会话结束时最后一行为`<endsession>`,
+//! 之后再回复脚本的执行结果
+
+use crate::{
+    config::RedisConfig,
+    error::Result,
+    model::{DebugCommand, DebugMode, DebugReply, DebugRequest, DebugSessionInfo, RespValue},
+    session::Sessions,
+};
+use chrono::Local;
+use redis::Value;
+use std::{
+    collections::BTreeSet,
+    ops::Deref,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// 前端监听的调试事件名称
+pub const DEBUG_EVENT: &str = "debug";
+
+/// 会话结束时服务端发送的标记
+const END_SESSION: &str = "<endsession>";
+/// 等待调试器回复的超时时间, 超时后结束会话
+const READ_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub struct LdbSession {
+    con: redis::Connection,
+    info: DebugSessionInfo,
+}
+
+impl LdbSession {
+    /// 开启调试模式并执行脚本, 脚本停在第一行
+    pub fn start(
+        config: &RedisConfig,
+        id: &str,
+        db: u8,
+        request: &DebugRequest,
+    ) -> Result<(Self, DebugReply)> {
+        let client = redis::Client::open(config.clone())?;
+        let mut con = client.get_connection()?;
+        con.set_read_timeout(Some(READ_TIMEOUT))?;
+        redis::cmd("SELECT").arg(db).query::<()>(&mut con)?;
+
+        let mode = match request.mode {
+            DebugMode::Yes => "YES",
+            DebugMode::Sync => "SYNC",
+        };
+        redis::cmd("SCRIPT")
+            .arg("DEBUG")
+            .arg(mode)
+            .query::<()>(&mut con)?;
+
+        let mut eval = redis::cmd("EVAL");
+        eval.arg(&request.script)
+            .arg(request.keys.len())
+            .arg(&request.keys)
+            .arg(&request.args);
+        con.send_packed_command(&eval.get_packed_command())?;
+
+        let mut session = Self {
+            con,
+            info: DebugSessionInfo {
+                session_id: String::new(),
+                id: id.to_string(),
+                db,
+                mode: request.mode,
+                line: None,
+                breakpoints: vec![],
+                started_at: Local::now().timestamp_millis(),
+            },
+        };
+        let reply = session.read_reply()?;
+        Ok((session, reply))
+    }
+
+    pub fn info(&self) -> &DebugSessionInfo {
+        &self.info
+    }
+
+    /// 发送调试命令, 断点变化后重新查询断点列表
+    pub fn send(&mut self, command: &DebugCommand) -> Result<DebugReply> {
+        let mut reply = self.request(command_args(command))?;
+        if !reply.ended
+            && matches!(
+                command,
+                DebugCommand::Break { .. } | DebugCommand::Delete { .. }
+            )
+        {
+            let list = self.request(vec!["break".to_string()])?;
+            let breakpoints: BTreeSet<_> = list
+                .lines
+                .iter()
+                .map(String::as_str)
+                .filter_map(breakpoint)
+                .collect();
+            self.info.breakpoints = breakpoints.into_iter().collect();
+            reply.breakpoints = self.info.breakpoints.clone();
+        }
+        Ok(reply)
+    }
+
+    fn request(&mut self, args: Vec<String>) -> Result<DebugReply> {
+        let mut cmd = redis::cmd(&args[0]);
+        cmd.arg(&args[1..]);
+        self.con.send_packed_command(&cmd.get_packed_command())?;
+        self.read_reply()
+    }
+
+    /// 读取一组日志行, 会话结束时继续读取脚本的执行结果
+    fn read_reply(&mut self) -> Result<DebugReply> {
+        let lines = match self.con.recv_response()? {
+            Value::Bulk(values) => values
+                .iter()
+                .map(|value| redis::from_redis_value(value).unwrap_or_default())
+                .collect(),
+            value => vec![redis::from_redis_value(&value).unwrap_or_default()],
+        };
+
+        let ended = lines.iter().any(|line: &String| line == END_SESSION);
+        let result = if ended {
+            Some(RespValue::from_result(self.con.recv_response())?)
+        } else {
+            None
+        };
+
+        if let Some(line) = lines
+            .iter()
+            .rev()
+            .map(String::as_str)
+            .find_map(stopped_line)
+        {
+            self.info.line = Some(line);
+        }
+
+        Ok(DebugReply {
+            session_id: self.info.session_id.clone(),
+            lines: lines
+                .into_iter()
+                .filter(|line| line != END_SESSION)
+                .collect(),
+            line: self.info.line,
+            breakpoints: self.info.breakpoints.clone(),
+            ended,
+            result,
+        })
+    }
+}
+
+/// 调试命令对应的参数, 与redis-cli --ldb中输入的命令一致
+fn command_args(command: &DebugCommand) -> Vec<String> {
+    let mut args = vec![];
+    match command {
+        DebugCommand::Step => args.push("step".to_string()),
+        DebugCommand::Continue => args.push("continue".to_string()),
+        DebugCommand::Abort => args.push("abort".to_string()),
+        DebugCommand::Break { line } => args.extend(["break".to_string(), line.to_string()]),
+        // 负数删除指定行的断点, 0删除所有断点
+        DebugCommand::Delete { line } => {
+            let line = line.map(|line| format!("-{line}"));
+            args.extend(["break".to_string(), line.unwrap_or_else(|| "0".to_string())])
+        }
+        // 第一个参数总是行号, 只指定上下文时用0表示当前行
+        DebugCommand::List { line, context } => {
+            args.push("list".to_string());
+            match (line, context) {
+                (_, Some(context)) => {
+                    args.extend([line.unwrap_or(0).to_string(), context.to_string()])
+                }
+                (Some(line), None) => args.push(line.to_string()),
+                (None, None) => {}
+            }
+        }
+        DebugCommand::Whole => args.push("whole".to_string()),
+        DebugCommand::Print { var } => {
+            args.push("print".to_string());
+            args.extend(var.iter().cloned());
+        }
+        DebugCommand::Eval { code } => args.extend(["eval".to_string(), code.clone()]),
+        DebugCommand::Redis { args: redis_args } => {
+            args.push("redis".to_string());
+            args.extend(redis_args.iter().cloned());
+        }
+        DebugCommand::Trace => args.push("trace".to_string()),
+        DebugCommand::Maxlen { len } => {
+            args.push("maxlen".to_string());
+            args.extend(len.iter().map(|n| n.to_string()));
+        }
+    }
+    args
+}
+
+/// `* Stopped at 4, stop reason = step over`
+fn stopped_line(line: &str) -> Option<u32> {
+    let rest = line.strip_prefix("* Stopped at ")?;
+    rest.split(',').next()?.trim().parse().ok()
+}
+
+/// 断点列表中的行: `  #4   return x`, 当前行为`->#4   return x`
+fn breakpoint(line: &str) -> Option<u32> {
+    let rest = line
+        .strip_prefix("  #")
+        .or_else(|| line.strip_prefix("->#"))?;
+    rest.split_whitespace().next()?.parse().ok()
+}
+
+/// 正在进行的调试会话
+#[derive(Default, Clone)]
+pub struct DebugState(Sessions<Arc<Mutex<LdbSession>>>);
+
+impl Deref for DebugState {
+    type Target = Sessions<Arc<Mutex<LdbSession>>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DebugState {
+    /// 保存会话并返回会话id
+    pub fn insert(&self, mut session: LdbSession) -> String {
+        let session_id = self.next_id("ldb");
+        session.info.session_id = session_id.clone();
+        let id = session.info.id.clone();
+        self.add(session_id.clone(), id, Arc::new(Mutex::new(session)));
+        session_id
+    }
+
+    /// 正在执行调试命令的会话不会出现在列表中
+    pub fn list(&self) -> Vec<DebugSessionInfo> {
+        let mut infos: Vec<_> = self
+            .values()
+            .iter()
+            .filter_map(|session| Some(session.try_lock().ok()?.info.clone()))
+            .collect();
+        infos.sort_by_key(|info| info.started_at);
+        infos
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{breakpoint, command_args, stopped_line, LdbSession};
+    use crate::{
+        config::RedisConfig,
+        model::{DebugCommand, DebugMode, DebugRequest, RespValue},
+    };
+
+    #[test]
+    fn list_args() {
+        let list = |line, context| command_args(&DebugCommand::List { line, context });
+        assert_eq!(list(None, None), ["list"]);
+        assert_eq!(list(Some(4), None), ["list", "4"]);
+        assert_eq!(list(None, Some(2)), ["list", "0", "2"]);
+        assert_eq!(list(Some(4), Some(2)), ["list", "4", "2"]);
+    }
+
+    #[test]
+    fn break_args() {
+        assert_eq!(
+            command_args(&DebugCommand::Break { line: 3 }),
+            ["break", "3"]
+        );
+        assert_eq!(
+            command_args(&DebugCommand::Delete { line: Some(3) }),
+            ["break", "-3"]
+        );
+        assert_eq!(
+            command_args(&DebugCommand::Delete { line: None }),
+            ["break", "0"]
+        );
+    }
+
+    #[test]
+    fn stopped() {
+        assert_eq!(
+            stopped_line("* Stopped at 4, stop reason = step over"),
+            Some(4)
+        );
+        assert_eq!(stopped_line("-> 4   return x"), None);
+        assert_eq!(
+            stopped_line("* Stopped at x, stop reason = step over"),
+            None
+        );
+    }
+
+    #[test]
+    fn breakpoints() {
+        assert_eq!(breakpoint("  #4   return x"), Some(4));
+        assert_eq!(breakpoint("->#12  local a = 1"), Some(12));
+        assert_eq!(breakpoint("-> 4   return x"), None);
+        assert_eq!(breakpoint("   4   return x"), None);
+        assert_eq!(breakpoint("1 breakpoints set:"), None);
+    }
+
+    /// 需要本地6379端口的redis: cargo test -- --ignored
+    #[test]
+    #[ignore]
+    fn local_server() {
+        let config = RedisConfig {
+            id: "test".to_string(),
+            name: "test".to_string(),
+            host: "127.0.0.1".to_string(),
+            port: 6379,
+            username: None,
+            password: None,
+            split: ":".to_string(),
+            cluster: false,
+        };
+        let request = DebugRequest {
+            script: "local a = 1\nlocal b = 2\nreturn a + b".to_string(),
+            keys: vec![],
+            args: vec![],
+            mode: DebugMode::Sync,
+        };
+        let send = |session: &mut LdbSession, command| {
+            session.send(&command).map_err(|err| err.message()).unwrap()
+        };
+
+        let (mut session, reply) = LdbSession::start(&config, "test", 0, &request)
+            .map_err(|err| err.message())
+            .unwrap();
+        assert_eq!(reply.line, Some(1));
+
+        let reply = send(&mut session, DebugCommand::Step);
+        assert_eq!(reply.line, Some(2));
+
+        let reply = send(&mut session, DebugCommand::Break { line: 3 });
+        assert_eq!(reply.breakpoints, [3]);
+
+        let reply = send(&mut session, DebugCommand::Continue);
+        assert!(!reply.ended);
+        assert_eq!(reply.line, Some(3));
+
+        let reply = send(&mut session, DebugCommand::Continue);
+        assert!(reply.ended);
+        assert_eq!(reply.result, Some(RespValue::Integer { value: 3 }));
+    }
+}
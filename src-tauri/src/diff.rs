@@ -0,0 +1,281 @@
+//! 比较两个键的值
+
+use crate::model::{
+    ElementDiff, HashResult, KeyContentDetail, KeyValue, LineDiff, LineTag, RedisValue,
+    StreamEntry, StreamResult, ValueComparison, ValueDiff, Z,
+};
+use serde_json::json;
+use similar::{ChangeTag, TextDiff};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// 单个键最多返回的差异数量
+const MAX_CHANGES: usize = 1000;
+/// 字符串比较最多返回的行数
+const MAX_LINES: usize = 5000;
+
+/// 比较同类型的两个值, 相同时返回None
+///
+/// 集合类型按元素比较: 哈希为字段, 集合和有序集合为成员, 列表为下标, stream为消息id
+pub fn diff_values(source: &KeyValue, target: &KeyValue) -> Option<ValueDiff> {
+    if source == target {
+        return None;
+    }
+
+    let mut diff = ValueDiff::new(source.type_name());
+    match (source, target) {
+        (KeyValue::String(source), KeyValue::String(target)) => {
+            diff.push(String::new(), Some(source.clone()), Some(target.clone()));
+        }
+        (KeyValue::List(source), KeyValue::List(target)) => diff_lists(&mut diff, source, target),
+        (KeyValue::Set(source), KeyValue::Set(target)) => diff_sets(&mut diff, source, target),
+        (KeyValue::ZSet(source), KeyValue::ZSet(target)) => {
+            diff_maps(&mut diff, zset_scores(source), zset_scores(target));
+        }
+        (KeyValue::Hash(source), KeyValue::Hash(target)) => {
+            diff_maps(&mut diff, source.clone(), target.clone());
+        }
+        (KeyValue::Stream(source), KeyValue::Stream(target)) => {
+            let entries = |entries: &[StreamEntry]| {
+                entries
+                    .iter()
+                    .map(|entry| (entry.id.clone(), json!(entry.fields).to_string()))
+                    .collect()
+            };
+            diff_maps(&mut diff, entries(source), entries(target));
+        }
+        _ => return None,
+    }
+
+    // 集合来自SSCAN, 相同的成员在两边的顺序可能不同, 按元素比较没有差异时视为相同
+    if diff.changes.is_empty() {
+        return None;
+    }
+    Some(diff)
+}
+
+/// 比较两个键的详细信息, 字符串按行比较, 其他类型按元素比较
+pub fn compare_details(source: &KeyContentDetail, target: &KeyContentDetail) -> ValueComparison {
+    if source.r#type != target.r#type {
+        return ValueComparison::Type;
+    }
+
+    let mut diff = ValueDiff::new(&source.r#type);
+    match (&source.value, &target.value) {
+        (RedisValue::String(source), RedisValue::String(target)) => {
+            return diff_text(source, target);
+        }
+        (RedisValue::List(source), RedisValue::List(target)) => {
+            diff_lists(&mut diff, source, target)
+        }
+        (RedisValue::Set(source), RedisValue::Set(target)) => diff_sets(&mut diff, source, target),
+        (RedisValue::ZSet(source), RedisValue::ZSet(target)) => {
+            diff_maps(&mut diff, zset_scores(source), zset_scores(target));
+        }
+        (RedisValue::Hash(source), RedisValue::Hash(target)) => {
+            let fields = |values: &[HashResult]| {
+                values
+                    .iter()
+                    .map(|h| (h.key.clone(), h.value.clone()))
+                    .collect()
+            };
+            diff_maps(&mut diff, fields(source), fields(target));
+        }
+        (RedisValue::Stream(source), RedisValue::Stream(target)) => {
+            let entries = |values: &[StreamResult]| {
+                values
+                    .iter()
+                    .map(|s| (s.id.clone(), s.value.clone()))
+                    .collect()
+            };
+            diff_maps(&mut diff, entries(source), entries(target));
+        }
+        _ => return ValueComparison::Type,
+    }
+
+    ValueComparison::Elements(diff)
+}
+
+/// 比较两个完整的值, 字符串按行比较, 其他类型按元素比较
+pub fn compare_values(source: &KeyValue, target: &KeyValue) -> ValueComparison {
+    match (source, target) {
+        (KeyValue::String(source), KeyValue::String(target)) => diff_text(source, target),
+        _ if source.type_name() != target.type_name() => ValueComparison::Type,
+        _ => ValueComparison::Elements(
+            diff_values(source, target).unwrap_or_else(|| ValueDiff::new(source.type_name())),
+        ),
+    }
+}
+
+/// 按行比较字符串, 两边都是json时格式化后比较, 忽略空白和字段顺序的差异
+pub fn diff_text(source: &str, target: &str) -> ValueComparison {
+    let parsed = serde_json::from_str::<serde_json::Value>(source)
+        .and_then(|source| Ok((source, serde_json::from_str::<serde_json::Value>(target)?)));
+    let (source, target, json) = match parsed {
+        Ok((source, target)) => (
+            serde_json::to_string_pretty(&source).unwrap_or_default(),
+            serde_json::to_string_pretty(&target).unwrap_or_default(),
+            true,
+        ),
+        Err(_) => (source.to_string(), target.to_string(), false),
+    };
+
+    let mut lines = vec![];
+    let mut truncated = false;
+    let text_diff = TextDiff::from_lines(&source, &target);
+    for change in text_diff.iter_all_changes() {
+        if lines.len() >= MAX_LINES {
+            truncated = true;
+            break;
+        }
+        lines.push(LineDiff {
+            tag: match change.tag() {
+                ChangeTag::Equal => LineTag::Equal,
+                ChangeTag::Delete => LineTag::Delete,
+                ChangeTag::Insert => LineTag::Insert,
+            },
+            source_line: change.old_index().map(|index| index + 1),
+            target_line: change.new_index().map(|index| index + 1),
+            text: change.value().trim_end_matches(['\r', '\n']).to_string(),
+        });
+    }
+
+    ValueComparison::Lines {
+        json,
+        equal: source == target,
+        lines,
+        truncated,
+    }
+}
+
+fn diff_lists(diff: &mut ValueDiff, source: &[String], target: &[String]) {
+    for index in 0..source.len().max(target.len()) {
+        let (source, target) = (source.get(index), target.get(index));
+        if source != target {
+            diff.push(index.to_string(), source.cloned(), target.cloned());
+        }
+    }
+}
+
+fn diff_sets(diff: &mut ValueDiff, source: &[String], target: &[String]) {
+    let source: BTreeSet<_> = source.iter().collect();
+    let target: BTreeSet<_> = target.iter().collect();
+    for member in source.symmetric_difference(&target) {
+        let present = |set: &BTreeSet<&String>| set.contains(member).then(|| member.to_string());
+        diff.push(member.to_string(), present(&source), present(&target));
+    }
+}
+
+fn zset_scores(values: &[Z]) -> BTreeMap<String, String> {
+    values
+        .iter()
+        .map(|z| (z.member.clone(), z.score.to_string()))
+        .collect()
+}
+
+fn diff_maps(
+    diff: &mut ValueDiff,
+    source: BTreeMap<String, String>,
+    target: BTreeMap<String, String>,
+) {
+    let elements: BTreeSet<_> = source.keys().chain(target.keys()).collect();
+    for element in elements {
+        let (source, target) = (source.get(element), target.get(element));
+        if source != target {
+            diff.push(element.clone(), source.cloned(), target.cloned());
+        }
+    }
+}
+
+impl ValueDiff {
+    fn new(r#type: &str) -> Self {
+        Self {
+            r#type: r#type.to_string(),
+            changes: vec![],
+            truncated: false,
+        }
+    }
+
+    fn push(&mut self, element: String, source: Option<String>, target: Option<String>) {
+        if self.changes.len() >= MAX_CHANGES {
+            self.truncated = true;
+            return;
+        }
+        self.changes.push(ElementDiff {
+            element,
+            source,
+            target,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff_values, MAX_CHANGES};
+    use crate::model::{KeyValue, Z};
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    /// 元素, 源中的值, 目标中的值
+    fn changes(diff: &super::ValueDiff) -> Vec<(&str, Option<&str>, Option<&str>)> {
+        diff.changes
+            .iter()
+            .map(|change| {
+                (
+                    change.element.as_str(),
+                    change.source.as_deref(),
+                    change.target.as_deref(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn set_order() {
+        let source = KeyValue::Set(strings(&["a", "b", "c"]));
+        let target = KeyValue::Set(strings(&["c", "a", "b"]));
+        assert!(diff_values(&source, &target).is_none());
+
+        let target = KeyValue::Set(strings(&["c", "a", "d"]));
+        let diff = diff_values(&source, &target).unwrap();
+        assert_eq!(
+            changes(&diff),
+            [("b", Some("b"), None), ("d", None, Some("d"))]
+        );
+    }
+
+    #[test]
+    fn zset_score() {
+        let source = KeyValue::ZSet(vec![Z::new(1.0, "a".into()), Z::new(2.0, "b".into())]);
+        let target = KeyValue::ZSet(vec![Z::new(2.0, "b".into()), Z::new(1.5, "a".into())]);
+        let diff = diff_values(&source, &target).unwrap();
+        assert_eq!(diff.r#type, "zset");
+        assert_eq!(changes(&diff), [("a", Some("1"), Some("1.5"))]);
+    }
+
+    #[test]
+    fn list_length() {
+        let source = KeyValue::List(strings(&["a", "b"]));
+        let target = KeyValue::List(strings(&["a", "x", "c"]));
+        let diff = diff_values(&source, &target).unwrap();
+        assert_eq!(
+            changes(&diff),
+            [("1", Some("b"), Some("x")), ("2", None, Some("c"))]
+        );
+    }
+
+    #[test]
+    fn truncated() {
+        let source = KeyValue::List((0..MAX_CHANGES + 10).map(|i| i.to_string()).collect());
+        let target = KeyValue::List(vec![]);
+        let diff = diff_values(&source, &target).unwrap();
+        assert_eq!(diff.changes.len(), MAX_CHANGES);
+        assert!(diff.truncated);
+
+        let source = KeyValue::List((0..MAX_CHANGES).map(|i| i.to_string()).collect());
+        let diff = diff_values(&source, &target).unwrap();
+        assert_eq!(diff.changes.len(), MAX_CHANGES);
+        assert!(!diff.truncated);
+    }
+}
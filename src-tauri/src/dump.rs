@@ -0,0 +1,74 @@
+use redis::{aio::ConnectionLike, Cmd, RedisResult};
+
+/// 键的序列化数据(DUMP)以及剩余的过期时间
+#[derive(Debug, Clone)]
+pub struct KeyDump {
+    /// 剩余过期时间(毫秒), -1表示永不过期
+    pub pttl: i64,
+    pub payload: Vec<u8>,
+}
+
+impl KeyDump {
+    /// RESTORE使用的ttl参数, 0表示永不过期
+    pub fn restore_ttl(&self) -> i64 {
+        self.pttl.max(0)
+    }
+
+    /// 生成RESTORE命令
+    pub fn restore_cmd(&self, key: &str, replace: bool) -> Cmd {
+        let mut cmd = redis::cmd("RESTORE");
+        cmd.arg(key).arg(self.restore_ttl()).arg(&self.payload);
+        if replace {
+            cmd.arg("REPLACE");
+        }
+        cmd
+    }
+}
+
+/// 使用DUMP + PTTL导出单个键, 键不存在时返回None
+pub async fn dump_key<C>(con: &mut C, key: &str) -> RedisResult<Option<KeyDump>>
+where
+    C: ConnectionLike + Send,
+{
+    let (payload, pttl): (Option<Vec<u8>>, i64) = redis::pipe()
+        .cmd("DUMP")
+        .arg(key)
+        .cmd("PTTL")
+        .arg(key)
+        .query_async(con)
+        .await?;
+
+    // 键可能在DUMP之后刚好过期
+    Ok(payload
+        .filter(|_| pttl != -2)
+        .map(|payload| KeyDump { pttl, payload }))
+}
+
+/// 批量导出同一节点上的多个键, 结果与传入的键一一对应
+pub async fn dump_keys<C>(con: &mut C, keys: &[String]) -> RedisResult<Vec<Option<KeyDump>>>
+where
+    C: ConnectionLike + Send,
+{
+    if keys.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut pipe = redis::pipe();
+    for key in keys {
+        pipe.cmd("DUMP").arg(key).cmd("PTTL").arg(key);
+    }
+    let values: Vec<redis::Value> = pipe.query_async(con).await?;
+
+    let mut dumps = Vec::with_capacity(keys.len());
+    for pair in values.chunks(2) {
+        let payload: Option<Vec<u8>> = redis::from_redis_value(&pair[0])?;
+        let pttl: i64 = redis::from_redis_value(&pair[1])?;
+        dumps.push(
+            payload
+                .filter(|_| pttl != -2)
+                .map(|payload| KeyDump { pttl, payload }),
+        );
+    }
+
+    Ok(dumps)
+}
@@ -6,6 +6,13 @@ use std::convert::From;
 pub struct SerializeError(Error);
 pub type Result<T, E = SerializeError> = std::result::Result<T, E>;
 
+impl SerializeError {
+    /// 错误信息
+    pub fn message(&self) -> String {
+        self.0.to_string()
+    }
+}
+
 impl Serialize for SerializeError {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -0,0 +1,162 @@
+//! 与redis的KEYS/SCAN MATCH一致的glob模式
+
+/// 判断字符串是否匹配glob模式, 支持`*`, `?`, `[abc]`, `[^a-z]`和`\`转义
+pub fn glob_match(pattern: &str, string: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let string: Vec<char> = string.chars().collect();
+    matches(&pattern, &string)
+}
+
+/// 转义glob模式中的特殊字符, 用于按前缀匹配
+pub fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// 遇到`*`时记录位置, 之后匹配失败时让最近的`*`多匹配一个字符再继续,
+/// 不会像递归那样因为多个`*`而出现指数级的回溯
+fn matches(pattern: &[char], string: &[char]) -> bool {
+    let (mut p, mut s) = (0, 0);
+    // 最近一个`*`之后的模式位置, 以及`*`已经匹配到的字符串位置
+    let mut star = None;
+
+    loop {
+        if pattern.get(p) == Some(&'*') {
+            p += 1;
+            star = Some((p, s));
+            continue;
+        }
+        if s == string.len() {
+            return pattern[p..].iter().all(|c| *c == '*');
+        }
+        if let Some(len) = match_one(&pattern[p..], string[s]) {
+            p += len;
+            s += 1;
+            continue;
+        }
+        match star {
+            Some((star_p, star_s)) => {
+                star = Some((star_p, star_s + 1));
+                p = star_p;
+                s = star_s + 1;
+            }
+            None => return false,
+        }
+    }
+}
+
+/// 用模式开头的一项匹配一个字符, 返回这一项在模式中占用的长度
+fn match_one(pattern: &[char], c: char) -> Option<usize> {
+    match pattern.first()? {
+        '?' => Some(1),
+        '[' => {
+            let (matched, rest) = match_class(&pattern[1..], c);
+            matched.then_some(pattern.len() - rest.len())
+        }
+        '\\' if pattern.len() > 1 => (pattern[1] == c).then_some(2),
+        p => (*p == c).then_some(1),
+    }
+}
+
+/// 匹配`[`之后的字符集合, 返回是否匹配以及`]`之后的模式
+fn match_class(pattern: &[char], c: char) -> (bool, &[char]) {
+    let negate = pattern.first() == Some(&'^');
+    let mut i = usize::from(negate);
+    let mut matched = false;
+
+    // 与redis一致, 缺少`]`时匹配到模式结尾
+    while i < pattern.len() && pattern[i] != ']' {
+        if pattern[i] == '\\' && i + 1 < pattern.len() {
+            matched |= pattern[i + 1] == c;
+            i += 2;
+        } else if i + 2 < pattern.len() && pattern[i + 1] == '-' && pattern[i + 2] != ']' {
+            let (start, end) = if pattern[i] <= pattern[i + 2] {
+                (pattern[i], pattern[i + 2])
+            } else {
+                (pattern[i + 2], pattern[i])
+            };
+            matched |= start <= c && c <= end;
+            i += 3;
+        } else {
+            matched |= pattern[i] == c;
+            i += 1;
+        }
+    }
+
+    (matched != negate, &pattern[(i + 1).min(pattern.len())..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape, glob_match};
+
+    #[test]
+    fn star() {
+        assert!(glob_match("*", ""));
+        assert!(glob_match("user:*", "user:1"));
+        assert!(glob_match("*:name", "user:1:name"));
+        assert!(glob_match("a*b*c", "aXbYbZc"));
+        assert!(!glob_match("a*b*c", "aXbYbZ"));
+        assert!(glob_match("**a", "bba"));
+    }
+
+    #[test]
+    fn many_stars() {
+        let key = "a".repeat(200);
+        assert!(!glob_match("*a*a*a*a*a*a*a*a*b", &key));
+        assert!(glob_match("*a*a*a*a*a*a*a*a*", &key));
+    }
+
+    #[test]
+    fn question_mark() {
+        assert!(glob_match("h?llo", "hello"));
+        assert!(glob_match("h?llo", "h中llo"));
+        assert!(!glob_match("h?llo", "hllo"));
+    }
+
+    #[test]
+    fn class() {
+        assert!(glob_match("h[ae]llo", "hallo"));
+        assert!(!glob_match("h[ae]llo", "hillo"));
+        assert!(glob_match("h[a-c]llo", "hbllo"));
+        assert!(glob_match("h[c-a]llo", "hbllo"));
+        assert!(glob_match("h[^a-z]llo", "h1llo"));
+        assert!(!glob_match("h[^a-z]llo", "hello"));
+        assert!(glob_match(r"[\]]", "]"));
+    }
+
+    #[test]
+    fn unclosed_class() {
+        // 缺少`]`时集合一直到模式结尾
+        assert!(glob_match("a[bc", "ab"));
+        assert!(!glob_match("a[bc", "abc"));
+    }
+
+    #[test]
+    fn backslash() {
+        assert!(glob_match(r"a\*b", "a*b"));
+        assert!(!glob_match(r"a\*b", "axb"));
+        assert!(glob_match(r"a\?", "a?"));
+        assert!(!glob_match(r"a\?", "ab"));
+        // 结尾的`\`匹配自身
+        assert!(glob_match("a\\", "a\\"));
+    }
+
+    #[test]
+    fn escape_round_trip() {
+        for value in ["plain", "a*b?c", "[x]", r"back\slash", "user:{1}"] {
+            assert!(glob_match(&escape(value), value));
+            assert!(glob_match(
+                &format!("{}*", escape(value)),
+                &format!("{value}:1")
+            ));
+        }
+        assert!(!glob_match(&escape("a*"), "abc"));
+    }
+}
@@ -0,0 +1,209 @@
+use crate::error::Result;
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tauri::Window;
+use tracing::{info, warn};
+
+/// 前端监听的任务事件名称
+pub const JOB_EVENT: &str = "job";
+
+/// 任务执行中产生数据的事件名称, 例如搜索到的匹配项
+pub const JOB_DATA_EVENT: &str = "job-data";
+
+/// 两次进度事件之间的最小间隔
+const EMIT_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 任务的取消标记
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// 正在运行的后台任务
+#[derive(Debug, Default, Clone)]
+pub struct Jobs {
+    next_id: Arc<AtomicU64>,
+    running: Arc<Mutex<HashMap<String, CancelToken>>>,
+}
+
+impl Jobs {
+    fn register(&self, kind: &str) -> (String, CancelToken) {
+        let id = format!(
+            "{kind}-{}",
+            self.next_id.fetch_add(1, Ordering::Relaxed) + 1
+        );
+        let token = CancelToken::default();
+        self.running
+            .lock()
+            .unwrap()
+            .insert(id.clone(), token.clone());
+        (id, token)
+    }
+
+    fn remove(&self, id: &str) {
+        self.running.lock().unwrap().remove(id);
+    }
+
+    /// 取消任务, 任务不存在或已结束时返回false
+    pub fn cancel(&self, id: &str) -> bool {
+        match self.running.lock().unwrap().get(id) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JobStatus {
+    Running,
+    Finished,
+    Cancelled,
+    Failed,
+}
+
+/// 发送给前端的任务事件
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobEvent<T> {
+    pub job_id: String,
+    pub kind: &'static str,
+    pub status: JobStatus,
+    pub data: Option<T>,
+    pub error: Option<String>,
+}
+
+/// 任务执行中产生的数据, 与进度不同, 每次都会发送
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobData<T> {
+    pub job_id: String,
+    pub kind: &'static str,
+    pub data: T,
+}
+
+/// 任务执行时的上下文, 用于检查取消状态和上报进度
+#[derive(Clone)]
+pub struct JobContext {
+    id: String,
+    kind: &'static str,
+    token: CancelToken,
+    window: Window,
+    last_emit: Arc<Mutex<Option<Instant>>>,
+}
+
+impl JobContext {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// 上报进度, 过于频繁的调用会被忽略
+    pub fn progress<T: Serialize + Clone>(&self, data: &T) {
+        {
+            let mut last_emit = self.last_emit.lock().unwrap();
+            if last_emit.is_some_and(|last| last.elapsed() < EMIT_INTERVAL) {
+                return;
+            }
+            *last_emit = Some(Instant::now());
+        }
+
+        self.emit(JobStatus::Running, Some(data.clone()), None);
+    }
+
+    /// 发送任务产生的数据, 不会被忽略
+    pub fn send<T: Serialize + Clone>(&self, data: T) {
+        let event = JobData {
+            job_id: self.id.clone(),
+            kind: self.kind,
+            data,
+        };
+        if let Err(err) = self.window.emit(JOB_DATA_EVENT, event) {
+            warn!(?err, job_id = self.id, "发送任务数据失败");
+        }
+    }
+
+    fn emit<T: Serialize + Clone>(
+        &self,
+        status: JobStatus,
+        data: Option<T>,
+        error: Option<String>,
+    ) {
+        let event = JobEvent {
+            job_id: self.id.clone(),
+            kind: self.kind,
+            status,
+            data,
+            error,
+        };
+        if let Err(err) = self.window.emit(JOB_EVENT, event) {
+            warn!(?err, job_id = self.id, "发送任务事件失败");
+        }
+    }
+}
+
+/// 在后台运行任务并返回任务id
+///
+/// 任务结束后会发送一次`Finished`/`Cancelled`/`Failed`事件, 携带任务的最终结果
+pub fn spawn_job<T, F, Fut>(window: Window, jobs: &Jobs, kind: &'static str, job: F) -> String
+where
+    T: Serialize + Clone + Send + 'static,
+    F: FnOnce(JobContext) -> Fut,
+    Fut: Future<Output = Result<T>> + Send + 'static,
+{
+    let (id, token) = jobs.register(kind);
+    let ctx = JobContext {
+        id: id.clone(),
+        kind,
+        token,
+        window,
+        last_emit: Arc::default(),
+    };
+
+    let fut = job(ctx.clone());
+    let jobs = jobs.clone();
+    tauri::async_runtime::spawn(async move {
+        let res = fut.await;
+        jobs.remove(&ctx.id);
+
+        match res {
+            Ok(data) => {
+                let status = if ctx.is_cancelled() {
+                    JobStatus::Cancelled
+                } else {
+                    JobStatus::Finished
+                };
+                info!(job_id = ctx.id, ?status, "任务结束");
+                ctx.emit(status, Some(data), None);
+            }
+            Err(err) => {
+                warn!(job_id = ctx.id, error = err.message(), "任务失败");
+                ctx.emit::<T>(JobStatus::Failed, None, Some(err.message()));
+            }
+        }
+    });
+
+    id
+}
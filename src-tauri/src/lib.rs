@@ -1,7 +1,25 @@
+pub mod analysis;
+pub mod cli_args;
 pub mod command;
 pub mod config;
+pub mod debug;
+pub mod diff;
+pub mod dump;
 pub mod error;
+pub mod glob;
+pub mod job;
 pub mod model;
 pub use command::*;
 pub mod macros;
 pub mod node_info;
+pub mod rdb;
+pub mod resp;
+pub mod scan;
+pub mod script;
+pub mod session;
+pub mod slot;
+pub mod snapshot;
+pub mod store;
+pub mod terminal;
+pub mod undo;
+pub mod value;
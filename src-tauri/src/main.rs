@@ -5,7 +5,13 @@
 
 use chrono::Local;
 use gedis::command::*;
-use gedis::RedisState;
+use gedis::debug::DebugState;
+use gedis::job::Jobs;
+use gedis::script::ScriptStore;
+use gedis::snapshot::SnapshotStore;
+use gedis::terminal::TerminalState;
+use gedis::undo::UndoState;
+use gedis::{OfflineState, RedisState};
 use tauri::Manager;
 use tracing::Level;
 use tracing_subscriber::fmt::{format::Writer, time::FormatTime};
@@ -35,6 +41,14 @@ fn main() {
             #[cfg(any(windows, target_os = "macos"))]
             set_shadow(&window, true).unwrap();
 
+            let data_dir = app.path_resolver().app_data_dir();
+            app.manage(SnapshotStore::new(
+                data_dir.as_ref().map(|dir| dir.join("history")),
+            ));
+            app.manage(ScriptStore::new(
+                data_dir.as_ref().map(|dir| dir.join("scripts")),
+            ));
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -57,12 +71,67 @@ fn main() {
             get_keys_by_db,
             get_key_info,
             rename_key,
+            get_zset_range,
+            copy_key,
+            move_key,
             set_key,
             set_key_ttl,
-            terminal
+            set_key_expire,
+            terminal,
+            cancel_job,
+            migrate_keys,
+            export_keys,
+            import_keys,
+            open_rdb,
+            close_rdb,
+            rdb_memory_report,
+            export_rdb_memory_report,
+            analyze_memory,
+            hot_keys_support,
+            find_hot_keys,
+            ttl_distribution,
+            search_values,
+            preview_namespace,
+            run_namespace_op,
+            preview_match_keys,
+            delete_match_keys,
+            list_undo,
+            undo_last,
+            restore_from_undo,
+            diff_keys,
+            sync_keys,
+            compare_keys,
+            list_key_versions,
+            get_key_version,
+            diff_key_version,
+            restore_key_version,
+            eval_script,
+            script_load,
+            script_exists,
+            script_flush,
+            list_scripts,
+            save_script,
+            delete_script,
+            debug_start,
+            debug_command,
+            debug_sessions,
+            debug_stop,
+            function_list,
+            function_load,
+            function_delete,
+            function_call,
+            function_dump,
+            function_restore,
+            terminal_open,
+            terminal_close
         ])
         .manage(RedisState::default())
         .manage(History::default())
+        .manage(Jobs::default())
+        .manage(OfflineState::default())
+        .manage(UndoState::default())
+        .manage(DebugState::default())
+        .manage(TerminalState::default())
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
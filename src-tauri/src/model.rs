@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Serialize)]
 #[serde(untagged)]
@@ -11,7 +12,7 @@ pub enum RedisValue {
     Stream(Vec<StreamResult>),
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Z {
     pub score: f64,
     pub member: String,
@@ -54,6 +55,8 @@ pub struct KeyContentDetail {
     pub r#type: String,
     pub label: String,
     pub ttl: i64,
+    pub pttl: i64,
+    pub expire_at: Option<i64>,
     pub size: usize,
     pub value: RedisValue,
 }
@@ -65,6 +68,18 @@ pub struct KeyInfo {
     pub r#type: String,
     pub label: String,
     pub ttl: i64,
+    pub pttl: i64,
+    pub expire_at: Option<i64>,
+    pub encoding: Option<String>,
+    /// MEMORY USAGE返回的字节数
+    pub memory: Option<u64>,
+    /// 空闲时间(秒), 只在非LFU淘汰策略下可用
+    pub idle_time: Option<u64>,
+    /// LFU访问频率, 只在LFU淘汰策略下可用
+    pub freq: Option<u64>,
+    pub refcount: Option<i64>,
+    /// 元素数量, 字符串为字节长度
+    pub elements: Option<u64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -82,3 +97,1084 @@ pub struct AddKeyInfo {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
 }
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CopyKeyInfo {
+    pub key: String,
+    pub new_key: String,
+    /// 目标数据库, 为空时复制到当前数据库
+    pub target_db: Option<u8>,
+    /// 目标键已存在时是否覆盖
+    #[serde(default)]
+    pub replace: bool,
+}
+
+/// 过期时间设置方式
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "camelCase")]
+pub enum KeyExpire {
+    /// 移除过期时间
+    Persist,
+    /// 相对过期时间(秒)
+    Seconds(i64),
+    /// 相对过期时间(毫秒)
+    Milliseconds(i64),
+    /// 绝对过期时间(unix时间戳, 秒)
+    At(i64),
+    /// 绝对过期时间(unix时间戳, 毫秒)
+    AtMilliseconds(i64),
+}
+
+/// 目标键已存在时的处理方式
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictPolicy {
+    /// 跳过
+    Skip,
+    /// 覆盖
+    Replace,
+    /// 添加后缀后写入
+    Rename,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrateOptions {
+    pub source_id: String,
+    pub source_db: u8,
+    pub pattern: String,
+    pub target_id: String,
+    pub target_db: u8,
+    pub conflict: ConflictPolicy,
+    /// 冲突策略为Rename时添加的后缀
+    pub rename_suffix: Option<String>,
+    /// 每批处理的键数量
+    pub batch_size: Option<usize>,
+    /// 同时执行的RESTORE数量
+    pub concurrency: Option<usize>,
+}
+
+impl MigrateOptions {
+    pub fn rename_suffix(&self) -> &str {
+        self.rename_suffix
+            .as_deref()
+            .filter(|suffix| !suffix.is_empty())
+            .unwrap_or(":migrated")
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyError {
+    pub key: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrateProgress {
+    pub scanned: u64,
+    pub migrated: u64,
+    pub renamed: u64,
+    pub skipped: u64,
+    pub failed: u64,
+    pub failures: Vec<KeyError>,
+}
+
+/// 键的完整数据, 用于导入导出
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyRecord {
+    pub key: String,
+    /// 剩余过期时间(毫秒), -1表示永不过期; 兼容之前导出的ttl字段
+    #[serde(alias = "ttl")]
+    pub pttl: i64,
+    #[serde(flatten)]
+    pub value: KeyValue,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "lowercase")]
+pub enum KeyValue {
+    String(String),
+    List(Vec<String>),
+    Set(Vec<String>),
+    ZSet(Vec<Z>),
+    Hash(BTreeMap<String, String>),
+    Stream(Vec<StreamEntry>),
+}
+
+impl KeyValue {
+    /// 元素数量, 字符串为字节长度
+    pub fn len(&self) -> usize {
+        match self {
+            KeyValue::String(value) => value.len(),
+            KeyValue::List(values) | KeyValue::Set(values) => values.len(),
+            KeyValue::ZSet(values) => values.len(),
+            KeyValue::Hash(values) => values.len(),
+            KeyValue::Stream(entries) => entries.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            KeyValue::String(_) => "string",
+            KeyValue::List(_) => "list",
+            KeyValue::Set(_) => "set",
+            KeyValue::ZSet(_) => "zset",
+            KeyValue::Hash(_) => "hash",
+            KeyValue::Stream(_) => "stream",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreamEntry {
+    pub id: String,
+    pub fields: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    /// 每行一个json对象, 包含键的类型, 过期时间和值
+    Json,
+    /// key,type,ttl,field,value, 只支持字符串和哈希
+    Csv,
+    /// 可直接用于`redis-cli --pipe`的RESP命令
+    Resp,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportOptions {
+    /// 匹配的键, 与keys同时为空时导出所有键
+    pub pattern: Option<String>,
+    /// 指定要导出的键
+    pub keys: Option<Vec<String>>,
+    pub format: ExportFormat,
+    /// 导出文件的路径
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportReport {
+    pub exported: u64,
+    /// 不存在或格式不支持的键
+    pub skipped: u64,
+    /// 读取失败的键, 例如值不是utf8编码
+    pub failed: u64,
+    pub failures: Vec<KeyError>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportFormat {
+    /// 导出的json行
+    Json,
+    /// 按列映射的csv, 有field列时写入哈希, 否则写入字符串
+    Csv,
+    /// RESP格式的命令, 例如`redis-cli --pipe`使用的文件
+    Resp,
+    /// 每行一条redis-cli命令
+    Cli,
+}
+
+/// csv的列, 可以是列名或从0开始的序号
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum CsvColumn {
+    Index(usize),
+    Name(String),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvMapping {
+    pub key_column: CsvColumn,
+    pub field_column: Option<CsvColumn>,
+    pub value_column: CsvColumn,
+    /// 过期时间列(毫秒)
+    pub ttl_column: Option<CsvColumn>,
+    #[serde(default = "default_has_headers")]
+    pub has_headers: bool,
+    pub delimiter: Option<char>,
+}
+
+fn default_has_headers() -> bool {
+    true
+}
+
+impl Default for CsvMapping {
+    /// 与导出的csv格式一致
+    fn default() -> Self {
+        Self {
+            key_column: CsvColumn::Name("key".to_string()),
+            field_column: Some(CsvColumn::Name("field".to_string())),
+            value_column: CsvColumn::Name("value".to_string()),
+            ttl_column: Some(CsvColumn::Name("ttl".to_string())),
+            has_headers: true,
+            delimiter: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportOptions {
+    pub path: String,
+    pub format: ImportFormat,
+    /// 只校验和统计, 不执行命令
+    #[serde(default)]
+    pub dry_run: bool,
+    /// 每批执行的记录数量
+    pub batch_size: Option<usize>,
+    pub csv: Option<CsvMapping>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineError {
+    pub line: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportProgress {
+    pub dry_run: bool,
+    /// 解析出的记录数量
+    pub total: u64,
+    /// 已处理的记录数量, 等于succeeded与failed之和
+    pub processed: u64,
+    /// 记录对应的命令数量
+    pub commands: u64,
+    pub succeeded: u64,
+    pub failed: u64,
+    pub errors: Vec<LineError>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RdbDbSummary {
+    pub db: u32,
+    pub keys: usize,
+    pub expires: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RdbSummary {
+    pub version: u32,
+    pub aux: BTreeMap<String, String>,
+    pub dbs: Vec<RdbDbSummary>,
+    /// 跳过的模块类型的键
+    pub skipped: u64,
+    /// 非utf8的键转义后与已有的键重名而跳过的数量
+    pub collisions: u64,
+}
+
+/// 单个键的内存占用
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyMemory {
+    pub db: u32,
+    pub key: String,
+    pub r#type: String,
+    pub encoding: Option<String>,
+    pub bytes: u64,
+    /// 元素数量, 字符串为字节长度
+    pub elements: u64,
+    /// 剩余过期时间(毫秒), -1表示永不过期
+    pub ttl: i64,
+}
+
+/// 按类型, 编码或命名空间汇总的内存占用
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupMemory {
+    pub name: String,
+    pub keys: u64,
+    pub bytes: u64,
+    pub elements: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryReport {
+    pub keys: u64,
+    pub bytes: u64,
+    pub by_type: Vec<GroupMemory>,
+    /// 名称为`类型/编码`
+    pub by_encoding: Vec<GroupMemory>,
+    /// 名称为空表示不属于任何命名空间
+    pub by_prefix: Vec<GroupMemory>,
+    /// 按剩余过期时间分桶, 名称为`persist`的是永不过期的键
+    pub by_ttl: Vec<GroupMemory>,
+    pub top_keys: Vec<KeyMemory>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryReportOptions {
+    /// 为空时统计所有数据库
+    pub db: Option<u32>,
+    /// 返回占用内存最大的前N个键
+    pub top: Option<usize>,
+    /// 命名空间的层级
+    pub depth: Option<usize>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyzeOptions {
+    pub pattern: Option<String>,
+    pub top: Option<usize>,
+    pub depth: Option<usize>,
+    /// 单次SCAN的COUNT
+    pub scan_count: Option<usize>,
+    /// 每批键之间的间隔(毫秒), 降低对服务器的压力
+    pub delay: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyzeProgress {
+    /// 已扫描完成的节点数
+    pub nodes: usize,
+    pub total_nodes: usize,
+    pub keys: u64,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HotKeyMethod {
+    /// 扫描键并查询OBJECT FREQ, 需要LFU淘汰策略
+    Lfu,
+    /// 在一段时间内通过MONITOR采样访问的键
+    Monitor,
+}
+
+/// 是否可以通过OBJECT FREQ查找热点键
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotKeySupport {
+    /// 读取失败时为空, 例如CONFIG命令被禁用
+    pub policy: Option<String>,
+    pub lfu: bool,
+    /// 不支持LFU时的原因
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotKeyOptions {
+    pub method: HotKeyMethod,
+    /// 只对Lfu有效
+    pub pattern: Option<String>,
+    pub top: Option<usize>,
+    pub depth: Option<usize>,
+    pub scan_count: Option<usize>,
+    /// 每批键之间的间隔(毫秒)
+    pub delay: Option<u64>,
+    /// MONITOR采样的时长(秒)
+    pub duration: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotKey {
+    pub key: String,
+    /// Lfu为OBJECT FREQ的对数计数器, Monitor为采样期间的访问次数
+    pub hits: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotKeyGroup {
+    pub name: String,
+    pub keys: u64,
+    pub hits: u64,
+    pub top: Vec<HotKey>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotKeyProgress {
+    /// Lfu为扫描的键数量, Monitor为采样的命令数量
+    pub scanned: u64,
+    /// 已用时间(毫秒)
+    pub elapsed: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotKeyReport {
+    pub method: HotKeyMethod,
+    pub scanned: u64,
+    pub elapsed: u64,
+    pub keys: Vec<HotKey>,
+    /// 名称为空表示不属于任何命名空间
+    pub by_prefix: Vec<HotKeyGroup>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TtlOptions {
+    /// 采样的键数量
+    pub sample: Option<usize>,
+    /// 预测未来N小时内过期的键
+    pub hours: Option<u32>,
+    pub depth: Option<usize>,
+}
+
+/// 未来N小时内将要过期的键, 按采样比例估算
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpiryForecast {
+    pub hour: u32,
+    pub keys: u64,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TtlReport {
+    /// 数据库中键的总数
+    pub total: u64,
+    pub sampled: u64,
+    /// 服务器是否支持MEMORY USAGE, 不支持时bytes都为0
+    pub memory: bool,
+    /// 采样的键按剩余过期时间分桶, 名称为`persist`的是永不过期的键
+    pub buckets: Vec<GroupMemory>,
+    /// 采样中永不过期的键按命名空间汇总
+    pub no_ttl: Vec<GroupMemory>,
+    /// 累计值, 第N项为未来N小时内过期的键
+    pub forecast: Vec<ExpiryForecast>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchOptions {
+    /// 搜索的键名匹配模式
+    pub pattern: Option<String>,
+    /// 要查找的子串或正则表达式
+    pub query: String,
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(default)]
+    pub ignore_case: bool,
+    pub scan_count: Option<usize>,
+    /// 每批键之间的间隔(毫秒)
+    pub delay: Option<u64>,
+    /// 匹配数量达到上限后停止搜索
+    pub max_matches: Option<usize>,
+}
+
+/// 匹配项在值中的位置
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum MatchLocation {
+    /// 字符串的值
+    Value,
+    /// 列表中的下标
+    Index { index: usize },
+    /// 集合或有序集合的成员
+    Member { score: Option<f64> },
+    /// 哈希的字段名
+    Field { field: String },
+    /// 哈希字段的值
+    HashValue { field: String },
+    /// stream消息中的字段名或值
+    Entry { id: String, field: String },
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueMatch {
+    pub key: String,
+    pub r#type: String,
+    pub location: MatchLocation,
+    /// 匹配的内容, 过长时会被截断
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchProgress {
+    pub scanned: u64,
+    /// 包含匹配项的键数量
+    pub keys: u64,
+    pub matches: u64,
+    /// 读取失败的键, 例如值不是utf8编码
+    pub failed: u64,
+    /// 匹配数量达到上限而提前结束
+    pub truncated: bool,
+}
+
+/// 有序集合的范围, min和max与redis命令的参数格式一致, 例如`(1.5`, `-inf`, `[a`, `-`
+#[derive(Debug, Deserialize)]
+#[serde(tag = "by", rename_all = "camelCase")]
+pub enum ZRangeBy {
+    Score { min: String, max: String },
+    Lex { min: String, max: String },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZRangeQuery {
+    #[serde(flatten)]
+    pub by: ZRangeBy,
+    /// 从大到小排列
+    #[serde(default)]
+    pub rev: bool,
+    pub offset: Option<usize>,
+    pub count: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZRangePage {
+    /// 范围内的成员总数
+    pub total: u64,
+    pub offset: usize,
+    pub has_more: bool,
+    pub values: Vec<Z>,
+}
+
+/// 对命名空间中所有键执行的操作
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "op", rename_all = "camelCase")]
+pub enum NamespaceOp {
+    /// 把前缀替换为新的前缀
+    Rename {
+        to: String,
+        /// 新键已存在时是否覆盖, 否则跳过
+        #[serde(default)]
+        replace: bool,
+    },
+    /// 设置或移除过期时间
+    Expire { expire: KeyExpire },
+    /// 统计键数量和占用内存
+    Stats,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamespaceOptions {
+    /// 命名空间前缀, 例如`a:b:`
+    pub prefix: String,
+    #[serde(flatten)]
+    pub op: NamespaceOp,
+    /// 每批处理的键数量
+    pub batch_size: Option<usize>,
+    /// 每批键之间的间隔(毫秒)
+    pub delay: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamespaceKey {
+    pub key: String,
+    /// 重命名后的键
+    pub new_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamespacePreview {
+    /// 匹配的键总数
+    pub count: u64,
+    pub keys: Vec<NamespaceKey>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamespaceProgress {
+    pub scanned: u64,
+    pub processed: u64,
+    /// 键已被删除, 或重命名时新键已存在
+    pub skipped: u64,
+    pub failed: u64,
+    /// 统计的占用内存, 服务器不支持MEMORY USAGE时为空
+    pub bytes: Option<u64>,
+    pub failures: Vec<KeyError>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyPreview {
+    /// 匹配的键总数
+    pub count: u64,
+    pub keys: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteOptions {
+    pub pattern: String,
+    /// 每次UNLINK的最大键数量
+    pub batch_size: Option<usize>,
+    /// 每批键之间的间隔(毫秒)
+    pub delay: Option<u64>,
+    /// 最多删除的键数量, 一般为预览时的数量, 超过时停止删除
+    pub max_keys: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteProgress {
+    pub scanned: u64,
+    pub deleted: u64,
+}
+
+/// 撤销记录的摘要
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoSummary {
+    pub id: u64,
+    pub op: String,
+    pub db: u8,
+    pub created_at: i64,
+    /// 保存的键数量
+    pub count: usize,
+    pub bytes: usize,
+    /// 超过上限时只保存了部分键
+    pub truncated: bool,
+    /// 前几个键
+    pub keys: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoReport {
+    pub id: u64,
+    pub restored: u64,
+    /// 按原来的过期时间已经过期, 不再恢复
+    pub expired: u64,
+    pub failed: u64,
+    pub failures: Vec<KeyError>,
+}
+
+/// 两个值之间的差异
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueDiff {
+    pub r#type: String,
+    pub changes: Vec<ElementDiff>,
+    /// 差异过多时只返回前一部分
+    pub truncated: bool,
+}
+
+/// 单个元素的差异, 值为空表示该元素只存在于另一边
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElementDiff {
+    /// 字符串为空, 哈希为字段, 集合和有序集合为成员, 列表为下标, stream为消息id
+    pub element: String,
+    /// 集合为成员本身, 有序集合为分数, stream为消息内容的json
+    pub source: Option<String>,
+    pub target: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffOptions {
+    pub source_id: String,
+    pub source_db: u8,
+    pub target_id: String,
+    pub target_db: u8,
+    pub pattern: String,
+    /// 过期时间相差超过该值(毫秒)时视为不同, 默认1000
+    pub ttl_tolerance: Option<i64>,
+    pub scan_count: Option<usize>,
+    /// 每批键之间的间隔(毫秒)
+    pub delay: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DiffKind {
+    OnlyInSource,
+    OnlyInTarget,
+    Type,
+    Ttl,
+    Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyDiff {
+    pub key: String,
+    pub kind: DiffKind,
+    pub source_type: Option<String>,
+    pub target_type: Option<String>,
+    /// 剩余过期时间(毫秒), -1表示永不过期
+    pub source_ttl: Option<i64>,
+    pub target_ttl: Option<i64>,
+    /// kind为Value时的差异
+    pub diff: Option<ValueDiff>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffProgress {
+    pub source_scanned: u64,
+    pub target_scanned: u64,
+    pub equal: u64,
+    pub only_in_source: u64,
+    pub only_in_target: u64,
+    pub type_diff: u64,
+    pub ttl_diff: u64,
+    pub value_diff: u64,
+    /// 读取失败的键, 例如值不是utf8编码
+    pub failed: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncOptions {
+    pub source_id: String,
+    pub source_db: u8,
+    pub target_id: String,
+    pub target_db: u8,
+    /// 要同步的键, 源中不存在的键会从目标中删除
+    pub keys: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncProgress {
+    pub total: u64,
+    pub written: u64,
+    pub deleted: u64,
+    pub failed: u64,
+    pub failures: Vec<KeyError>,
+}
+
+/// 一个连接和数据库中的键
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyLocation {
+    pub id: String,
+    pub db: u8,
+    pub key: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineTag {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// 字符串按行比较的结果, 行号从1开始
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineDiff {
+    pub tag: LineTag,
+    pub source_line: Option<usize>,
+    pub target_line: Option<usize>,
+    pub text: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ValueComparison {
+    /// 类型不同, 不逐项比较
+    Type,
+    /// 另一边的键不存在
+    Missing,
+    /// 字符串按行比较, json为true时两边都按格式化后的json比较
+    Lines {
+        json: bool,
+        equal: bool,
+        lines: Vec<LineDiff>,
+        truncated: bool,
+    },
+    /// 哈希, 集合, 有序集合, 列表和stream按元素比较
+    Elements(ValueDiff),
+}
+
+impl ValueComparison {
+    pub fn is_equal(&self) -> bool {
+        match self {
+            ValueComparison::Type | ValueComparison::Missing => false,
+            ValueComparison::Lines { equal, .. } => *equal,
+            ValueComparison::Elements(diff) => diff.changes.is_empty(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyComparison {
+    pub source: KeyContentDetail,
+    pub target: KeyContentDetail,
+    pub equal: bool,
+    pub diff: ValueComparison,
+}
+
+/// 键在修改前的值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snapshot {
+    pub id: u64,
+    pub db: u8,
+    /// 修改键的操作, 例如set, rename
+    pub op: String,
+    /// 保存时间(unix时间戳, 毫秒)
+    pub created_at: i64,
+    /// 序列化后的字节数
+    pub bytes: usize,
+    pub record: KeyRecord,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotSummary {
+    pub id: u64,
+    pub db: u8,
+    pub key: String,
+    pub op: String,
+    pub created_at: i64,
+    pub r#type: String,
+    /// 元素数量, 字符串为字节长度
+    pub size: usize,
+    pub bytes: usize,
+}
+
+/// 历史版本与键的当前值的比较结果
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionComparison {
+    pub version: Snapshot,
+    /// 键不存在时为空
+    pub current: Option<KeyRecord>,
+    pub equal: bool,
+    pub diff: ValueComparison,
+}
+
+/// 带类型的命令返回值
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RespValue {
+    Nil,
+    Integer {
+        value: i64,
+    },
+    /// 不是utf8编码时text为redis-cli格式的转义字符串, bytes为原始字节
+    ///
+    /// repr总是redis-cli格式的转义字符串, 不包含两边的引号
+    Bulk {
+        text: String,
+        repr: String,
+        bytes: Option<Vec<u8>>,
+    },
+    Array {
+        values: Vec<RespValue>,
+    },
+    /// 状态回复, 例如OK, QUEUED
+    Status {
+        value: String,
+    },
+    /// 服务端返回的错误, code为错误码, 例如ERR, WRONGTYPE, NOSCRIPT
+    Error {
+        code: String,
+        message: String,
+    },
+}
+
+/// 执行lua脚本, 有script时先尝试EVALSHA, 脚本未缓存时使用EVAL
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvalRequest {
+    pub script: Option<String>,
+    pub sha: Option<String>,
+    #[serde(default)]
+    pub keys: Vec<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvalResult {
+    pub sha: String,
+    pub result: RespValue,
+    /// 执行耗时(毫秒)
+    pub elapsed: u64,
+}
+
+/// 保存在本地的lua脚本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedScript {
+    /// 为空时新建
+    #[serde(default)]
+    pub id: u64,
+    pub name: String,
+    pub script: String,
+    #[serde(default)]
+    pub keys: Vec<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub updated_at: i64,
+}
+
+/// YES: 在子进程中调试, 结束后回滚数据的修改; SYNC: 阻塞服务端, 保留数据的修改
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DebugMode {
+    Yes,
+    Sync,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugRequest {
+    pub script: String,
+    #[serde(default)]
+    pub keys: Vec<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    pub mode: DebugMode,
+}
+
+/// 调试命令, 与redis-cli --ldb中的命令对应
+#[derive(Debug, Deserialize)]
+#[serde(tag = "cmd", rename_all = "camelCase")]
+pub enum DebugCommand {
+    Step,
+    Continue,
+    /// 停止脚本, SYNC模式下已执行的修改不会回滚
+    Abort,
+    Break {
+        line: u32,
+    },
+    /// line为空时删除所有断点
+    Delete {
+        line: Option<u32>,
+    },
+    List {
+        line: Option<u32>,
+        context: Option<u32>,
+    },
+    Whole,
+    /// var为空时打印所有局部变量
+    Print {
+        var: Option<String>,
+    },
+    Eval {
+        code: String,
+    },
+    Redis {
+        args: Vec<String>,
+    },
+    Trace,
+    Maxlen {
+        len: Option<u32>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugReply {
+    pub session_id: String,
+    /// 调试器输出的日志
+    pub lines: Vec<String>,
+    /// 脚本当前停在的行
+    pub line: Option<u32>,
+    pub breakpoints: Vec<u32>,
+    pub ended: bool,
+    /// 会话结束时脚本的执行结果
+    pub result: Option<RespValue>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugSessionInfo {
+    pub session_id: String,
+    /// 连接id
+    pub id: String,
+    pub db: u8,
+    pub mode: DebugMode,
+    pub line: Option<u32>,
+    pub breakpoints: Vec<u32>,
+    pub started_at: i64,
+}
+
+/// FUNCTION LIST返回的函数库
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionLibrary {
+    pub name: String,
+    pub engine: String,
+    pub functions: Vec<FunctionInfo>,
+    /// 只有WITHCODE时才有
+    pub code: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionInfo {
+    pub name: String,
+    pub description: Option<String>,
+    /// no-writes, allow-oom, allow-stale, no-cluster等
+    pub flags: Vec<String>,
+}
+
+/// 调用函数, read_only时使用FCALL_RO
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FcallRequest {
+    pub function: String,
+    #[serde(default)]
+    pub keys: Vec<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+/// FUNCTION RESTORE的策略: APPEND遇到同名库时报错, REPLACE覆盖同名库, FLUSH先删除所有库
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum FunctionRestorePolicy {
+    Append,
+    Replace,
+    Flush,
+}
+
+/// 终端会话的状态, 每次执行命令后返回
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalSessionInfo {
+    pub session_id: String,
+    /// 连接id
+    pub id: String,
+    pub db: u8,
+    /// 是否在MULTI事务中
+    pub transaction: bool,
+    /// 事务中已入队的命令数
+    pub queued: u32,
+    /// 当前认证的用户
+    pub user: String,
+    pub started_at: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalReply {
+    pub result: RespValue,
+    pub session: TerminalSessionInfo,
+}
@@ -0,0 +1,300 @@
+//! 紧凑编码的解析: ziplist, listpack, intset, zipmap
+
+use super::reader::Reader;
+use crate::error::Result;
+
+/// listpack中的元素, 可能是字符串或整数
+#[derive(Debug, Clone)]
+pub enum LpValue {
+    Str(Vec<u8>),
+    Int(i64),
+}
+
+impl LpValue {
+    pub fn into_bytes(self) -> Vec<u8> {
+        match self {
+            LpValue::Str(bytes) => bytes,
+            LpValue::Int(value) => value.to_string().into_bytes(),
+        }
+    }
+
+    pub fn as_int(&self) -> Result<i64> {
+        match self {
+            LpValue::Int(value) => Ok(*value),
+            LpValue::Str(bytes) => std::str::from_utf8(bytes)
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .ok_or_else(|| "listpack元素不是整数".into()),
+        }
+    }
+}
+
+/// 解析ziplist
+pub fn ziplist(data: &[u8]) -> Result<Vec<Vec<u8>>> {
+    let mut reader = Reader::new(data);
+    // zlbytes, zltail
+    reader.skip(8)?;
+    let len = reader.u16_le()? as usize;
+
+    let mut entries = Vec::with_capacity(len.min(reader.remaining()));
+    loop {
+        let prevlen = reader.u8()?;
+        if prevlen == 0xff {
+            break;
+        }
+        if prevlen == 0xfe {
+            reader.skip(4)?;
+        }
+
+        let encoding = reader.u8()?;
+        let entry = match encoding >> 6 {
+            0b00 => reader.bytes((encoding & 0x3f) as usize)?.to_vec(),
+            0b01 => {
+                let len = (((encoding & 0x3f) as usize) << 8) | reader.u8()? as usize;
+                reader.bytes(len)?.to_vec()
+            }
+            0b10 => {
+                let len = reader.u32_be()? as usize;
+                reader.bytes(len)?.to_vec()
+            }
+            _ => {
+                let value = match encoding {
+                    0xc0 => reader.i16_le()? as i64,
+                    0xd0 => reader.i32_le()? as i64,
+                    0xe0 => reader.i64_le()?,
+                    0xf0 => reader.i24_le()?,
+                    0xfe => reader.u8()? as i8 as i64,
+                    0xf1..=0xfd => (encoding & 0x0f) as i64 - 1,
+                    _ => return Err(format!("未知的ziplist编码: {encoding:#x}").into()),
+                };
+                value.to_string().into_bytes()
+            }
+        };
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+/// 解析listpack
+pub fn listpack(data: &[u8]) -> Result<Vec<LpValue>> {
+    let mut reader = Reader::new(data);
+    // 总字节数
+    reader.skip(4)?;
+    let len = reader.u16_le()? as usize;
+
+    let mut entries = Vec::with_capacity(len.min(reader.remaining()));
+    loop {
+        let start = reader.pos();
+        let encoding = reader.u8()?;
+        let entry = if encoding == 0xff {
+            break;
+        } else if encoding & 0x80 == 0 {
+            LpValue::Int((encoding & 0x7f) as i64)
+        } else if encoding & 0xc0 == 0x80 {
+            LpValue::Str(reader.bytes((encoding & 0x3f) as usize)?.to_vec())
+        } else if encoding & 0xe0 == 0xc0 {
+            let value = (((encoding & 0x1f) as u16) << 8) | reader.u8()? as u16;
+            // 13位有符号整数
+            LpValue::Int(((value << 3) as i16 >> 3) as i64)
+        } else if encoding & 0xf0 == 0xe0 {
+            let len = (((encoding & 0x0f) as usize) << 8) | reader.u8()? as usize;
+            LpValue::Str(reader.bytes(len)?.to_vec())
+        } else {
+            match encoding {
+                0xf0 => {
+                    let len = reader.u32_le()? as usize;
+                    LpValue::Str(reader.bytes(len)?.to_vec())
+                }
+                0xf1 => LpValue::Int(reader.i16_le()? as i64),
+                0xf2 => LpValue::Int(reader.i24_le()?),
+                0xf3 => LpValue::Int(reader.i32_le()? as i64),
+                0xf4 => LpValue::Int(reader.i64_le()?),
+                _ => return Err(format!("未知的listpack编码: {encoding:#x}").into()),
+            }
+        };
+
+        // 跳过backlen
+        let entry_len = reader.pos() - start;
+        reader.skip(backlen_size(entry_len))?;
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+fn backlen_size(len: usize) -> usize {
+    match len {
+        0..=127 => 1,
+        128..=16382 => 2,
+        16383..=2097150 => 3,
+        2097151..=268435454 => 4,
+        _ => 5,
+    }
+}
+
+/// 解析intset
+pub fn intset(data: &[u8]) -> Result<Vec<Vec<u8>>> {
+    let mut reader = Reader::new(data);
+    let encoding = reader.u32_le()?;
+    let len = reader.u32_le()? as usize;
+
+    let mut values = Vec::with_capacity(len.min(reader.remaining()));
+    for _ in 0..len {
+        let value = match encoding {
+            2 => reader.i16_le()? as i64,
+            4 => reader.i32_le()? as i64,
+            8 => reader.i64_le()?,
+            _ => return Err(format!("未知的intset编码: {encoding}").into()),
+        };
+        values.push(value.to_string().into_bytes());
+    }
+
+    Ok(values)
+}
+
+/// 解析zipmap(redis 2.6之前的哈希编码), 返回交替的field和value
+pub fn zipmap(data: &[u8]) -> Result<Vec<Vec<u8>>> {
+    let mut reader = Reader::new(data);
+    // zmlen
+    reader.skip(1)?;
+
+    let mut entries = vec![];
+    loop {
+        let Some(len) = zipmap_len(&mut reader)? else {
+            break;
+        };
+        let field = reader.bytes(len)?.to_vec();
+
+        let len = zipmap_len(&mut reader)?.ok_or("zipmap数据不完整")?;
+        let free = reader.u8()? as usize;
+        let value = reader.bytes(len)?.to_vec();
+        reader.skip(free)?;
+
+        entries.push(field);
+        entries.push(value);
+    }
+
+    Ok(entries)
+}
+
+fn zipmap_len(reader: &mut Reader) -> Result<Option<usize>> {
+    match reader.u8()? {
+        0xff => Ok(None),
+        0xfe => Ok(Some(reader.u32_le()? as usize)),
+        len => Ok(Some(len as usize)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: Vec<Vec<u8>>) -> Vec<String> {
+        values
+            .into_iter()
+            .map(|value| String::from_utf8(value).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn intset_values() {
+        let mut data = vec![2, 0, 0, 0, 3, 0, 0, 0];
+        for value in [1i16, -2, 300] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        let values = intset(&data).map_err(|err| err.message()).unwrap();
+        assert_eq!(strings(values), ["1", "-2", "300"]);
+
+        let mut data = vec![8, 0, 0, 0, 1, 0, 0, 0];
+        data.extend_from_slice(&i64::MIN.to_le_bytes());
+        let values = intset(&data).map_err(|err| err.message()).unwrap();
+        assert_eq!(strings(values), [i64::MIN.to_string()]);
+    }
+
+    #[test]
+    fn intset_invalid() {
+        // 元素数量超过实际数据
+        assert!(intset(&[2, 0, 0, 0, 0xff, 0xff, 0xff, 0xff, 1, 0]).is_err());
+        assert!(intset(&[3, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0]).is_err());
+        assert!(intset(&[2, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn ziplist_entries() {
+        let mut data = vec![0; 8];
+        data.extend_from_slice(&5u16.to_le_bytes());
+        // 6位长度的字符串
+        data.extend_from_slice(&[0x00, 0x02, b'a', b'b']);
+        // 16位整数
+        data.extend_from_slice(&[0x04, 0xc0, 0xe8, 0x03]);
+        // 8位整数
+        data.extend_from_slice(&[0x04, 0xfe, 0x85]);
+        // 4位立即数, 0xf1表示0
+        data.extend_from_slice(&[0x03, 0xf3]);
+        // 14位长度的字符串
+        data.extend_from_slice(&[0x02, 0x40, 0x03, b'x', b'y', b'z']);
+        data.push(0xff);
+
+        let values = ziplist(&data).map_err(|err| err.message()).unwrap();
+        assert_eq!(strings(values), ["ab", "1000", "-123", "2", "xyz"]);
+    }
+
+    #[test]
+    fn ziplist_invalid() {
+        let mut data = vec![0; 8];
+        data.extend_from_slice(&u16::MAX.to_le_bytes());
+        data.extend_from_slice(&[0x00, 0x05, b'a']);
+        assert!(ziplist(&data).is_err());
+
+        let mut data = vec![0; 8];
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&[0x00, 0xc1]);
+        assert!(ziplist(&data).is_err());
+    }
+
+    fn lp_entry(data: &mut Vec<u8>, entry: &[u8]) {
+        data.extend_from_slice(entry);
+        data.push(entry.len() as u8);
+    }
+
+    #[test]
+    fn listpack_entries() {
+        let mut data = vec![0; 4];
+        data.extend_from_slice(&5u16.to_le_bytes());
+        // 7位无符号整数
+        lp_entry(&mut data, &[0x05]);
+        // 6位长度的字符串
+        lp_entry(&mut data, &[0x82, b'h', b'i']);
+        // 13位有符号整数
+        lp_entry(&mut data, &[0xdf, 0xff]);
+        // 16位整数
+        lp_entry(&mut data, &[0xf1, 0x30, 0xf8]);
+        // 12位长度的字符串
+        lp_entry(&mut data, &[0xe0, 0x03, b'a', b'b', b'c']);
+        data.push(0xff);
+
+        let values: Vec<_> = listpack(&data)
+            .map_err(|err| err.message())
+            .unwrap()
+            .into_iter()
+            .map(LpValue::into_bytes)
+            .collect();
+        assert_eq!(strings(values), ["5", "hi", "-1", "-2000", "abc"]);
+    }
+
+    #[test]
+    fn listpack_invalid() {
+        let mut data = vec![0; 4];
+        data.extend_from_slice(&u16::MAX.to_le_bytes());
+        data.extend_from_slice(&[0xf0, 0xff, 0xff, 0xff, 0xff, b'a']);
+        assert!(listpack(&data).is_err());
+
+        let mut data = vec![0; 4];
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&[0xf5, 0x01]);
+        assert!(listpack(&data).is_err());
+
+        assert!(LpValue::Str(b"abc".to_vec()).as_int().is_err());
+    }
+}
@@ -0,0 +1,104 @@
+use crate::error::Result;
+
+/// 一个回溯引用最少占2个字节, 最多展开为264个字节
+const MAX_RATIO: usize = 132;
+
+/// 解压LZF压缩的数据
+pub fn decompress(input: &[u8], out_len: usize) -> Result<Vec<u8>> {
+    // out_len来自文件, 按输入长度能展开的上限限制预分配的容量
+    let mut output = Vec::with_capacity(out_len.min(input.len().saturating_mul(MAX_RATIO)));
+    let mut i = 0;
+
+    while i < input.len() {
+        let ctrl = input[i] as usize;
+        i += 1;
+
+        if ctrl < 32 {
+            // 字面量: 后面ctrl + 1个字节原样复制
+            let len = ctrl + 1;
+            let literal = input.get(i..i + len).ok_or("lzf数据不完整")?;
+            output.extend_from_slice(literal);
+            i += len;
+        } else {
+            // 回溯引用: 高3位为长度, 其余为距离
+            let mut len = ctrl >> 5;
+            if len == 7 {
+                len += *input.get(i).ok_or("lzf数据不完整")? as usize;
+                i += 1;
+            }
+            len += 2;
+
+            let low = *input.get(i).ok_or("lzf数据不完整")? as usize;
+            i += 1;
+            let distance = ((ctrl & 0x1f) << 8) + low + 1;
+            let start = output
+                .len()
+                .checked_sub(distance)
+                .ok_or("lzf回溯引用越界")?;
+
+            // 引用区间可能与正在写入的区间重叠, 需要逐字节复制
+            for j in 0..len {
+                let byte = output[start + j];
+                output.push(byte);
+            }
+        }
+
+        if output.len() > out_len {
+            return Err("lzf解压后的长度不正确".into());
+        }
+    }
+
+    if output.len() != out_len {
+        return Err("lzf解压后的长度不正确".into());
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decompress;
+
+    fn run(input: &[u8], out_len: usize) -> Result<Vec<u8>, String> {
+        decompress(input, out_len).map_err(|err| err.message())
+    }
+
+    #[test]
+    fn literal() {
+        assert_eq!(run(&[0x02, b'a', b'b', b'c'], 3), Ok(b"abc".to_vec()));
+    }
+
+    #[test]
+    fn back_reference() {
+        // 字面量"abc", 然后从3个字节之前复制3个字节
+        assert_eq!(
+            run(&[0x02, b'a', b'b', b'c', 0x20, 0x02], 6),
+            Ok(b"abcabc".to_vec())
+        );
+    }
+
+    #[test]
+    fn overlapping_reference() {
+        // 从1个字节之前复制5个字节, 引用区间与写入区间重叠
+        assert_eq!(run(&[0x00, b'a', 0x60, 0x00], 6), Ok(b"aaaaaa".to_vec()));
+    }
+
+    #[test]
+    fn long_reference() {
+        // 长度字段为7时由下一个字节补充长度: 7 + 3 + 2
+        assert_eq!(run(&[0x00, b'x', 0xe0, 0x03, 0x00], 13), Ok(vec![b'x'; 13]));
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert!(run(&[0x05, b'a'], 6).is_err());
+        assert!(run(&[0x20, 0x05], 3).is_err());
+        assert!(run(&[0x02, b'a', b'b', b'c'], 4).is_err());
+        assert!(run(&[0x00, b'x', 0xe0, 0xff, 0x00], 4).is_err());
+    }
+
+    #[test]
+    fn huge_out_len() {
+        assert!(run(&[0x00, b'a'], usize::MAX).is_err());
+    }
+}
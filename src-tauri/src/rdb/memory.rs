@@ -0,0 +1,104 @@
+//! 估算键在redis中占用的内存, 参考redis-rdb-tools的估算方式
+//!
+//! 结果只是近似值: 紧凑编码以rdb中的字节数为准, 其他编码按redis的数据结构和jemalloc的分配规则计算
+
+use super::RdbEntry;
+use crate::model::KeyValue;
+
+const POINTER: u64 = 8;
+const ROBJ: u64 = 16;
+const DICT_ENTRY: u64 = 24;
+const DICT: u64 = 96;
+const QUICKLIST: u64 = 40;
+const QUICKLIST_NODE: u64 = 32;
+const LIST_NODE: u64 = 24;
+const SKIPLIST: u64 = 32;
+/// 跳表节点: ele, score, backward以及平均约1.33层的level
+const SKIPLIST_NODE: u64 = 24 + 16 * 2;
+/// quicklist单个节点的默认大小(list-max-listpack-size -2)
+const QUICKLIST_NODE_SIZE: u64 = 8 * 1024;
+
+/// 估算单个键占用的内存(字节), 包括键本身和过期时间
+pub fn estimate(key: &str, entry: &RdbEntry) -> u64 {
+    let mut size = DICT_ENTRY + sds(key.len() as u64) + ROBJ + POINTER;
+    if entry.expire_at.is_some() {
+        size += DICT_ENTRY + POINTER;
+    }
+
+    size + value_size(entry)
+}
+
+fn value_size(entry: &RdbEntry) -> u64 {
+    let compact = malloc(entry.rdb_size);
+    match (&entry.value, entry.encoding) {
+        (KeyValue::String(_), "int") => 0,
+        // embstr与robj分配在一起
+        (KeyValue::String(value), "embstr") => malloc(ROBJ + 3 + value.len() as u64) - ROBJ,
+        (KeyValue::String(value), _) => sds(value.len() as u64),
+        (_, "listpack" | "ziplist" | "intset" | "zipmap") => compact,
+        (KeyValue::List(_), "quicklist") => {
+            let nodes = entry.rdb_size / QUICKLIST_NODE_SIZE + 1;
+            QUICKLIST + nodes * malloc(QUICKLIST_NODE) + compact
+        }
+        (KeyValue::List(values), _) => values
+            .iter()
+            .map(|v| malloc(LIST_NODE) + ROBJ + sds(v.len() as u64))
+            .sum(),
+        (KeyValue::Set(values), _) => {
+            dict(values.len() as u64)
+                + values
+                    .iter()
+                    .map(|v| malloc(DICT_ENTRY) + sds(v.len() as u64))
+                    .sum::<u64>()
+        }
+        (KeyValue::Hash(values), _) => {
+            dict(values.len() as u64)
+                + values
+                    .iter()
+                    .map(|(k, v)| malloc(DICT_ENTRY) + sds(k.len() as u64) + sds(v.len() as u64))
+                    .sum::<u64>()
+        }
+        (KeyValue::ZSet(values), _) => {
+            SKIPLIST
+                + dict(values.len() as u64)
+                + values
+                    .iter()
+                    .map(|z| {
+                        malloc(DICT_ENTRY) + malloc(SKIPLIST_NODE) + sds(z.member.len() as u64)
+                    })
+                    .sum::<u64>()
+        }
+        // stream由rax和listpack组成, 与rdb中的大小接近
+        (KeyValue::Stream(_), _) => compact,
+    }
+}
+
+/// 字典本身以及桶数组, 桶的数量为大于元素数量的2的幂
+fn dict(len: u64) -> u64 {
+    DICT + malloc(len.max(4).next_power_of_two() * POINTER)
+}
+
+/// sds字符串: 头部长度随字符串长度变化, 末尾有一个'\0'
+fn sds(len: u64) -> u64 {
+    let header = match len {
+        0..=31 => 1,
+        32..=255 => 3,
+        256..=65535 => 5,
+        _ => 9,
+    };
+    malloc(len + header + 1)
+}
+
+/// jemalloc实际分配的大小: 128字节以内按16字节对齐, 之后每次翻倍区间内分为4档
+fn malloc(size: u64) -> u64 {
+    if size <= 8 {
+        return 8;
+    }
+    if size <= 128 {
+        return (size + 15) / 16 * 16;
+    }
+
+    let power = 64 - (size - 1).leading_zeros();
+    let step = 1 << (power - 3);
+    (size + step - 1) / step * step
+}
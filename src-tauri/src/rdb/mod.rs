@@ -0,0 +1,54 @@
+//! 离线解析rdb文件
+
+mod encoding;
+mod lzf;
+pub mod memory;
+mod parser;
+mod reader;
+
+use crate::{error::Result, model::KeyValue};
+use std::collections::BTreeMap;
+
+/// 解析后的rdb文件
+#[derive(Debug, Default)]
+pub struct RdbFile {
+    pub version: u32,
+    /// 辅助字段, 例如redis-ver, ctime, used-mem
+    pub aux: BTreeMap<String, String>,
+    /// 非utf8的键按redis-cli的格式转义
+    pub dbs: BTreeMap<u32, BTreeMap<String, RdbEntry>>,
+    /// 跳过的模块类型的键
+    pub skipped: u64,
+    /// 非utf8的键转义后与已有的键重名而跳过的数量
+    pub collisions: u64,
+}
+
+#[derive(Debug)]
+pub struct RdbEntry {
+    pub value: KeyValue,
+    /// 绝对过期时间(unix时间戳, 毫秒)
+    pub expire_at: Option<i64>,
+    /// 与OBJECT ENCODING一致的编码
+    pub encoding: &'static str,
+    /// 值在rdb文件中占用的字节数
+    pub rdb_size: u64,
+}
+
+impl RdbFile {
+    pub fn parse(data: &[u8]) -> Result<RdbFile> {
+        parser::parse(data)
+    }
+
+    pub fn open(path: &str) -> Result<RdbFile> {
+        let data = std::fs::read(path).map_err(|err| format!("读取rdb文件失败: {err}"))?;
+        Self::parse(&data)
+    }
+
+    /// 生成rdb文件时的时间(毫秒), 用于计算剩余过期时间
+    pub fn created_at(&self) -> Option<i64> {
+        self.aux
+            .get("ctime")
+            .and_then(|ctime| ctime.parse::<i64>().ok())
+            .map(|ctime| ctime * 1000)
+    }
+}
@@ -0,0 +1,545 @@
+use super::{
+    encoding::{intset, listpack, ziplist, zipmap, LpValue},
+    reader::Reader,
+    RdbEntry, RdbFile,
+};
+use crate::{
+    error::Result,
+    model::{KeyValue, StreamEntry, Z},
+    resp::repr,
+};
+use std::collections::BTreeMap;
+
+const OPCODE_SLOT_INFO: u8 = 0xf4;
+const OPCODE_FUNCTION2: u8 = 0xf5;
+const OPCODE_FUNCTION_PRE_GA: u8 = 0xf6;
+const OPCODE_MODULE_AUX: u8 = 0xf7;
+const OPCODE_IDLE: u8 = 0xf8;
+const OPCODE_FREQ: u8 = 0xf9;
+const OPCODE_AUX: u8 = 0xfa;
+const OPCODE_RESIZEDB: u8 = 0xfb;
+const OPCODE_EXPIRETIME_MS: u8 = 0xfc;
+const OPCODE_EXPIRETIME: u8 = 0xfd;
+const OPCODE_SELECTDB: u8 = 0xfe;
+const OPCODE_EOF: u8 = 0xff;
+
+const TYPE_STRING: u8 = 0;
+const TYPE_LIST: u8 = 1;
+const TYPE_SET: u8 = 2;
+const TYPE_ZSET: u8 = 3;
+const TYPE_HASH: u8 = 4;
+const TYPE_ZSET_2: u8 = 5;
+const TYPE_MODULE_2: u8 = 7;
+const TYPE_HASH_ZIPMAP: u8 = 9;
+const TYPE_LIST_ZIPLIST: u8 = 10;
+const TYPE_SET_INTSET: u8 = 11;
+const TYPE_ZSET_ZIPLIST: u8 = 12;
+const TYPE_HASH_ZIPLIST: u8 = 13;
+const TYPE_LIST_QUICKLIST: u8 = 14;
+const TYPE_STREAM_LISTPACKS: u8 = 15;
+const TYPE_HASH_LISTPACK: u8 = 16;
+const TYPE_ZSET_LISTPACK: u8 = 17;
+const TYPE_LIST_QUICKLIST_2: u8 = 18;
+const TYPE_STREAM_LISTPACKS_2: u8 = 19;
+const TYPE_SET_LISTPACK: u8 = 20;
+const TYPE_STREAM_LISTPACKS_3: u8 = 21;
+
+const MODULE_OPCODE_EOF: u64 = 0;
+const MODULE_OPCODE_SINT: u64 = 1;
+const MODULE_OPCODE_UINT: u64 = 2;
+const MODULE_OPCODE_FLOAT: u64 = 3;
+const MODULE_OPCODE_DOUBLE: u64 = 4;
+const MODULE_OPCODE_STRING: u64 = 5;
+
+const QUICKLIST_NODE_PLAIN: u64 = 1;
+
+const STREAM_ITEM_FLAG_DELETED: i64 = 1;
+const STREAM_ITEM_FLAG_SAMEFIELDS: i64 = 2;
+
+/// 当前支持的最高rdb版本(redis 7.4, 不包括带字段过期时间的哈希)
+const MAX_VERSION: u32 = 12;
+
+pub fn parse(data: &[u8]) -> Result<RdbFile> {
+    let mut reader = Reader::new(data);
+    if reader.bytes(5)? != b"REDIS" {
+        return Err("不是有效的rdb文件".into());
+    }
+    let version: u32 = std::str::from_utf8(reader.bytes(4)?)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .ok_or("无效的rdb版本")?;
+    if version > MAX_VERSION {
+        return Err(format!("不支持的rdb版本: {version}").into());
+    }
+
+    let mut rdb = RdbFile {
+        version,
+        ..Default::default()
+    };
+    let mut db = 0;
+    let mut expire_at = None;
+
+    loop {
+        let opcode = reader.u8()?;
+        match opcode {
+            OPCODE_EOF => break,
+            OPCODE_SELECTDB => db = reader.len()? as u32,
+            OPCODE_RESIZEDB => {
+                reader.len()?;
+                reader.len()?;
+            }
+            OPCODE_EXPIRETIME_MS => expire_at = Some(reader.i64_le()?),
+            OPCODE_EXPIRETIME => expire_at = Some(reader.i32_le()? as i64 * 1000),
+            OPCODE_AUX => {
+                let key = reader.string()?;
+                let value = reader.string()?;
+                rdb.aux.insert(
+                    String::from_utf8_lossy(&key).to_string(),
+                    String::from_utf8_lossy(&value).to_string(),
+                );
+            }
+            OPCODE_IDLE => {
+                reader.len()?;
+            }
+            OPCODE_FREQ => {
+                reader.u8()?;
+            }
+            OPCODE_MODULE_AUX => {
+                // module id, when_opcode, when
+                reader.len()?;
+                reader.len()?;
+                reader.len()?;
+                skip_module_value(&mut reader)?;
+            }
+            OPCODE_FUNCTION2 => {
+                reader.string()?;
+            }
+            OPCODE_FUNCTION_PRE_GA => return Err("不支持redis 7.0 rc版本的函数数据".into()),
+            OPCODE_SLOT_INFO => {
+                // slot id, slot size, expires slot size
+                reader.len()?;
+                reader.len()?;
+                reader.len()?;
+            }
+            typ => {
+                let key = reader.string()?;
+                let expire = expire_at.take();
+
+                let start = reader.pos();
+                let Some((value, encoding)) = read_value(&mut reader, typ)? else {
+                    rdb.skipped += 1;
+                    continue;
+                };
+
+                let keys = rdb.dbs.entry(db).or_default();
+                let name = key_name(key);
+                if keys.contains_key(&name) {
+                    rdb.collisions += 1;
+                    continue;
+                }
+                keys.insert(
+                    name,
+                    RdbEntry {
+                        value,
+                        expire_at: expire,
+                        encoding,
+                        rdb_size: (reader.pos() - start) as u64,
+                    },
+                );
+            }
+        }
+    }
+
+    Ok(rdb)
+}
+
+/// 非utf8的键按redis-cli的格式转义, 避免不同的二进制键转换后重名
+fn key_name(key: Vec<u8>) -> String {
+    String::from_utf8(key).unwrap_or_else(|err| repr(err.as_bytes()))
+}
+
+fn lossy(bytes: Vec<u8>) -> String {
+    String::from_utf8(bytes)
+        .unwrap_or_else(|err| String::from_utf8_lossy(err.as_bytes()).to_string())
+}
+
+fn strings(values: Vec<Vec<u8>>) -> Vec<String> {
+    values.into_iter().map(lossy).collect()
+}
+
+fn lp_strings(values: Vec<LpValue>) -> Vec<String> {
+    values.into_iter().map(|v| lossy(v.into_bytes())).collect()
+}
+
+fn pairs_to_hash(values: Vec<String>) -> Result<BTreeMap<String, String>> {
+    let mut iter = values.into_iter();
+    let mut hash = BTreeMap::new();
+    while let Some(field) = iter.next() {
+        let value = iter.next().ok_or("哈希的元素数量不正确")?;
+        hash.insert(field, value);
+    }
+    Ok(hash)
+}
+
+fn pairs_to_zset(values: Vec<String>) -> Result<Vec<Z>> {
+    let mut iter = values.into_iter();
+    let mut zset = vec![];
+    while let Some(member) = iter.next() {
+        let score = iter.next().ok_or("有序集合的元素数量不正确")?;
+        let score = score.parse().map_err(|_| format!("无效的分数: {score}"))?;
+        zset.push(Z::new(score, member));
+    }
+    Ok(zset)
+}
+
+/// 读取值以及对应的编码(与OBJECT ENCODING一致), 模块类型的值会被跳过
+fn read_value(reader: &mut Reader, typ: u8) -> Result<Option<(KeyValue, &'static str)>> {
+    let value = match typ {
+        TYPE_STRING => {
+            let encoding = if reader.peek_int_encoded() {
+                "int"
+            } else {
+                "raw"
+            };
+            let value = reader.string()?;
+            let encoding = match encoding {
+                "raw" if value.len() <= 44 => "embstr",
+                encoding => encoding,
+            };
+            (KeyValue::String(lossy(value)), encoding)
+        }
+        TYPE_LIST => {
+            let len = reader.len()?;
+            let values = (0..len)
+                .map(|_| reader.string().map(lossy))
+                .collect::<Result<_>>()?;
+            (KeyValue::List(values), "linkedlist")
+        }
+        TYPE_SET => {
+            let len = reader.len()?;
+            let values = (0..len)
+                .map(|_| reader.string().map(lossy))
+                .collect::<Result<_>>()?;
+            (KeyValue::Set(values), "hashtable")
+        }
+        TYPE_ZSET | TYPE_ZSET_2 => {
+            let len = reader.len()?;
+            let mut values = Vec::with_capacity((len as usize).min(reader.remaining()));
+            for _ in 0..len {
+                let member = lossy(reader.string()?);
+                let score = if typ == TYPE_ZSET_2 {
+                    reader.f64_le()?
+                } else {
+                    reader.string_double()?
+                };
+                values.push(Z::new(score, member));
+            }
+            (KeyValue::ZSet(values), "skiplist")
+        }
+        TYPE_HASH => {
+            let len = reader.len()?;
+            let mut values = BTreeMap::new();
+            for _ in 0..len {
+                let field = lossy(reader.string()?);
+                let value = lossy(reader.string()?);
+                values.insert(field, value);
+            }
+            (KeyValue::Hash(values), "hashtable")
+        }
+        TYPE_MODULE_2 => {
+            reader.len()?;
+            skip_module_value(reader)?;
+            return Ok(None);
+        }
+        TYPE_HASH_ZIPMAP => {
+            let values = strings(zipmap(&reader.string()?)?);
+            (KeyValue::Hash(pairs_to_hash(values)?), "zipmap")
+        }
+        TYPE_LIST_ZIPLIST => {
+            let values = strings(ziplist(&reader.string()?)?);
+            (KeyValue::List(values), "ziplist")
+        }
+        TYPE_SET_INTSET => {
+            let values = strings(intset(&reader.string()?)?);
+            (KeyValue::Set(values), "intset")
+        }
+        TYPE_ZSET_ZIPLIST => {
+            let values = strings(ziplist(&reader.string()?)?);
+            (KeyValue::ZSet(pairs_to_zset(values)?), "ziplist")
+        }
+        TYPE_HASH_ZIPLIST => {
+            let values = strings(ziplist(&reader.string()?)?);
+            (KeyValue::Hash(pairs_to_hash(values)?), "ziplist")
+        }
+        TYPE_LIST_QUICKLIST => {
+            let len = reader.len()?;
+            let mut values = vec![];
+            for _ in 0..len {
+                values.extend(strings(ziplist(&reader.string()?)?));
+            }
+            (KeyValue::List(values), "quicklist")
+        }
+        TYPE_LIST_QUICKLIST_2 => {
+            let len = reader.len()?;
+            let mut values = vec![];
+            for _ in 0..len {
+                let container = reader.len()?;
+                let data = reader.string()?;
+                if container == QUICKLIST_NODE_PLAIN {
+                    values.push(lossy(data));
+                } else {
+                    values.extend(lp_strings(listpack(&data)?));
+                }
+            }
+            (KeyValue::List(values), "quicklist")
+        }
+        TYPE_HASH_LISTPACK => {
+            let values = lp_strings(listpack(&reader.string()?)?);
+            (KeyValue::Hash(pairs_to_hash(values)?), "listpack")
+        }
+        TYPE_ZSET_LISTPACK => {
+            let values = lp_strings(listpack(&reader.string()?)?);
+            (KeyValue::ZSet(pairs_to_zset(values)?), "listpack")
+        }
+        TYPE_SET_LISTPACK => {
+            let values = lp_strings(listpack(&reader.string()?)?);
+            (KeyValue::Set(values), "listpack")
+        }
+        TYPE_STREAM_LISTPACKS | TYPE_STREAM_LISTPACKS_2 | TYPE_STREAM_LISTPACKS_3 => {
+            (KeyValue::Stream(read_stream(reader, typ)?), "stream")
+        }
+        _ => return Err(format!("不支持的值类型: {typ}").into()),
+    };
+
+    Ok(Some(value))
+}
+
+/// 跳过模块序列化的数据
+fn skip_module_value(reader: &mut Reader) -> Result<()> {
+    loop {
+        match reader.len()? {
+            MODULE_OPCODE_EOF => return Ok(()),
+            MODULE_OPCODE_SINT | MODULE_OPCODE_UINT => {
+                reader.len()?;
+            }
+            MODULE_OPCODE_FLOAT => reader.skip(4)?,
+            MODULE_OPCODE_DOUBLE => reader.skip(8)?,
+            MODULE_OPCODE_STRING => {
+                reader.string()?;
+            }
+            opcode => return Err(format!("未知的模块数据类型: {opcode}").into()),
+        }
+    }
+}
+
+/// 读取stream的所有消息, 消费者组等元数据只读取不保存
+fn read_stream(reader: &mut Reader, typ: u8) -> Result<Vec<StreamEntry>> {
+    let mut entries = vec![];
+
+    let nodes = reader.len()?;
+    for _ in 0..nodes {
+        let node_key = reader.string()?;
+        if node_key.len() != 16 {
+            return Err("无效的stream节点id".into());
+        }
+        let master_ms = u64::from_be_bytes(node_key[0..8].try_into().unwrap_or_default());
+        let master_seq = u64::from_be_bytes(node_key[8..16].try_into().unwrap_or_default());
+
+        let items = listpack(&reader.string()?)?;
+        read_stream_node(items, master_ms, master_seq, &mut entries)?;
+    }
+
+    // length, last_id
+    reader.len()?;
+    reader.len()?;
+    reader.len()?;
+    if typ >= TYPE_STREAM_LISTPACKS_2 {
+        // first_id, max_deleted_entry_id, entries_added
+        for _ in 0..5 {
+            reader.len()?;
+        }
+    }
+
+    let groups = reader.len()?;
+    for _ in 0..groups {
+        reader.string()?;
+        // last_id
+        reader.len()?;
+        reader.len()?;
+        if typ >= TYPE_STREAM_LISTPACKS_2 {
+            // entries_read
+            reader.len()?;
+        }
+
+        let pending = reader.len()?;
+        for _ in 0..pending {
+            // id, delivery_time, delivery_count
+            reader.skip(16)?;
+            reader.skip(8)?;
+            reader.len()?;
+        }
+
+        let consumers = reader.len()?;
+        for _ in 0..consumers {
+            reader.string()?;
+            // seen_time
+            reader.skip(8)?;
+            if typ >= TYPE_STREAM_LISTPACKS_3 {
+                // active_time
+                reader.skip(8)?;
+            }
+            let pending = reader.len()?;
+            let size = (pending as usize)
+                .checked_mul(16)
+                .ok_or("rdb数据长度溢出")?;
+            reader.skip(size)?;
+        }
+    }
+
+    Ok(entries)
+}
+
+/// 解析stream节点的listpack: 主条目记录公共字段, 后续条目记录与主id的差值
+fn read_stream_node(
+    items: Vec<LpValue>,
+    master_ms: u64,
+    master_seq: u64,
+    entries: &mut Vec<StreamEntry>,
+) -> Result<()> {
+    let mut iter = items.into_iter();
+    let mut next = move || iter.next().ok_or("stream节点数据不完整");
+
+    let count = next()?.as_int()?;
+    let deleted = next()?.as_int()?;
+    let master_fields_len = next()?.as_int()?;
+    let master_fields = (0..master_fields_len)
+        .map(|_| next().map(|v| lossy(v.into_bytes())))
+        .collect::<Result<Vec<_>, _>>()?;
+    // 主条目的结束标记
+    next()?;
+
+    for _ in 0..count.saturating_add(deleted) {
+        let flags = next()?.as_int()?;
+        let ms = master_ms.wrapping_add(next()?.as_int()? as u64);
+        let seq = master_seq.wrapping_add(next()?.as_int()? as u64);
+
+        let mut fields = BTreeMap::new();
+        if flags & STREAM_ITEM_FLAG_SAMEFIELDS != 0 {
+            for field in &master_fields {
+                fields.insert(field.clone(), lossy(next()?.into_bytes()));
+            }
+        } else {
+            let len = next()?.as_int()?;
+            for _ in 0..len {
+                let field = lossy(next()?.into_bytes());
+                let value = lossy(next()?.into_bytes());
+                fields.insert(field, value);
+            }
+        }
+        // lp-count
+        next()?;
+
+        if flags & STREAM_ITEM_FLAG_DELETED == 0 {
+            entries.push(StreamEntry {
+                id: format!("{ms}-{seq}"),
+                fields,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn int(value: i64) -> LpValue {
+        LpValue::Int(value)
+    }
+
+    fn text(value: &str) -> LpValue {
+        LpValue::Str(value.as_bytes().to_vec())
+    }
+
+    /// 只包含字符串键的rdb文件
+    fn string_rdb(keys: &[&[u8]]) -> Vec<u8> {
+        let mut data = b"REDIS0009".to_vec();
+        for key in keys {
+            data.extend([TYPE_STRING, key.len() as u8]);
+            data.extend(*key);
+            data.extend([1, b'v']);
+        }
+        data.push(OPCODE_EOF);
+        data
+    }
+
+    #[test]
+    fn binary_keys() {
+        let rdb = parse(&string_rdb(&[b"\xff", b"\xfe", b"k", br"\xff"]))
+            .map_err(|err| err.message())
+            .unwrap();
+        let keys: Vec<_> = rdb.dbs[&0].keys().map(String::as_str).collect();
+        assert_eq!(keys, [r"\xfe", r"\xff", "k"]);
+        assert_eq!(rdb.collisions, 1);
+    }
+
+    #[test]
+    fn stream_node() {
+        let items = vec![
+            // count, deleted, 主条目的字段
+            int(2),
+            int(1),
+            int(1),
+            text("f"),
+            int(0),
+            // 与主条目字段相同
+            int(STREAM_ITEM_FLAG_SAMEFIELDS),
+            int(0),
+            int(0),
+            text("v1"),
+            int(3),
+            // 已删除的条目
+            int(STREAM_ITEM_FLAG_SAMEFIELDS | STREAM_ITEM_FLAG_DELETED),
+            int(1),
+            int(0),
+            text("x"),
+            int(3),
+            // 字段不同的条目
+            int(0),
+            int(2),
+            int(5),
+            int(1),
+            text("a"),
+            text("b"),
+            int(5),
+        ];
+
+        let mut entries = vec![];
+        read_stream_node(items, 100, 7, &mut entries)
+            .map_err(|err| err.message())
+            .unwrap();
+        assert_eq!(
+            entries,
+            [
+                StreamEntry {
+                    id: "100-7".to_string(),
+                    fields: BTreeMap::from([("f".to_string(), "v1".to_string())]),
+                },
+                StreamEntry {
+                    id: "102-12".to_string(),
+                    fields: BTreeMap::from([("a".to_string(), "b".to_string())]),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn stream_node_truncated() {
+        let items = vec![int(1), int(0), int(1), text("f"), int(0), int(0), int(0)];
+        assert!(read_stream_node(items, 0, 0, &mut vec![]).is_err());
+
+        let items = vec![int(i64::MAX), int(i64::MAX), int(0), int(0)];
+        assert!(read_stream_node(items, 0, 0, &mut vec![]).is_err());
+    }
+}
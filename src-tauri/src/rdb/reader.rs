@@ -0,0 +1,156 @@
+use super::lzf;
+use crate::error::Result;
+
+/// 按字节读取rdb数据
+pub struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+/// rdb的长度编码, 特殊编码用于整数和压缩字符串
+pub enum Length {
+    Len(u64),
+    Encoded(u8),
+}
+
+const ENC_INT8: u8 = 0;
+const ENC_INT16: u8 = 1;
+const ENC_INT32: u8 = 2;
+const ENC_LZF: u8 = 3;
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// 剩余未读取的字节数, 用于限制按文件中的长度预分配的容量
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    pub fn bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.pos.checked_add(len).ok_or("rdb数据长度溢出")?;
+        let bytes = self.data.get(self.pos..end).ok_or("rdb文件不完整")?;
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    pub fn skip(&mut self, len: usize) -> Result<()> {
+        self.bytes(len)?;
+        Ok(())
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut buf = [0; N];
+        buf.copy_from_slice(self.bytes(N)?);
+        Ok(buf)
+    }
+
+    pub fn u8(&mut self) -> Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub fn u16_le(&mut self) -> Result<u16> {
+        Ok(u16::from_le_bytes(self.array()?))
+    }
+
+    pub fn i16_le(&mut self) -> Result<i16> {
+        Ok(i16::from_le_bytes(self.array()?))
+    }
+
+    pub fn i24_le(&mut self) -> Result<i64> {
+        let [a, b, c] = self.array()?;
+        // 左移后再算术右移, 保留符号位
+        Ok((i32::from_le_bytes([0, a, b, c]) >> 8) as i64)
+    }
+
+    pub fn u32_le(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    pub fn i32_le(&mut self) -> Result<i32> {
+        Ok(i32::from_le_bytes(self.array()?))
+    }
+
+    pub fn u32_be(&mut self) -> Result<u32> {
+        Ok(u32::from_be_bytes(self.array()?))
+    }
+
+    pub fn i64_le(&mut self) -> Result<i64> {
+        Ok(i64::from_le_bytes(self.array()?))
+    }
+
+    pub fn u64_be(&mut self) -> Result<u64> {
+        Ok(u64::from_be_bytes(self.array()?))
+    }
+
+    pub fn f64_le(&mut self) -> Result<f64> {
+        Ok(f64::from_le_bytes(self.array()?))
+    }
+
+    pub fn length(&mut self) -> Result<Length> {
+        let first = self.u8()?;
+        let len = match first >> 6 {
+            0b00 => Length::Len((first & 0x3f) as u64),
+            0b01 => Length::Len((((first & 0x3f) as u64) << 8) | self.u8()? as u64),
+            0b10 => match first {
+                0x80 => Length::Len(self.u32_be()? as u64),
+                0x81 => Length::Len(self.u64_be()?),
+                _ => return Err(format!("未知的长度编码: {first:#x}").into()),
+            },
+            _ => Length::Encoded(first & 0x3f),
+        };
+        Ok(len)
+    }
+
+    pub fn len(&mut self) -> Result<u64> {
+        match self.length()? {
+            Length::Len(len) => Ok(len),
+            Length::Encoded(_) => Err("此处应为长度, 实际为特殊编码".into()),
+        }
+    }
+
+    /// 读取字符串, 同时处理整数编码和LZF压缩
+    pub fn string(&mut self) -> Result<Vec<u8>> {
+        match self.length()? {
+            Length::Len(len) => Ok(self.bytes(len as usize)?.to_vec()),
+            Length::Encoded(ENC_INT8) => Ok((self.u8()? as i8).to_string().into_bytes()),
+            Length::Encoded(ENC_INT16) => Ok(self.i16_le()?.to_string().into_bytes()),
+            Length::Encoded(ENC_INT32) => Ok(self.i32_le()?.to_string().into_bytes()),
+            Length::Encoded(ENC_LZF) => {
+                let compressed_len = self.len()? as usize;
+                let len = self.len()? as usize;
+                lzf::decompress(self.bytes(compressed_len)?, len)
+            }
+            Length::Encoded(enc) => Err(format!("未知的字符串编码: {enc}").into()),
+        }
+    }
+
+    /// 字符串是否以整数编码保存, 不移动读取位置
+    pub fn peek_int_encoded(&self) -> bool {
+        self.data
+            .get(self.pos)
+            .is_some_and(|first| first >> 6 == 0b11 && first & 0x3f != ENC_LZF)
+    }
+
+    /// 旧版本有序集合中以字符串保存的分数
+    pub fn string_double(&mut self) -> Result<f64> {
+        let score = match self.u8()? {
+            253 => f64::NAN,
+            254 => f64::INFINITY,
+            255 => f64::NEG_INFINITY,
+            len => {
+                let bytes = self.bytes(len as usize)?;
+                std::str::from_utf8(bytes)
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .ok_or("无效的分数")?
+            }
+        };
+        Ok(score)
+    }
+}
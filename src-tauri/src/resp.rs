@@ -0,0 +1,237 @@
+//! 将命令的返回值转换为带类型的结构, 前端据此按redis-cli的格式展示
+
+use crate::model::RespValue;
+use futures::{future::BoxFuture, FutureExt};
+use redis::{RedisError, Value};
+use std::io;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt};
+
+impl From<Value> for RespValue {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Nil => RespValue::Nil,
+            Value::Int(value) => RespValue::Integer { value },
+            Value::Data(data) => bulk(data),
+            Value::Bulk(values) => RespValue::Array {
+                values: values.into_iter().map(RespValue::from).collect(),
+            },
+            Value::Status(value) => RespValue::Status { value },
+            Value::Okay => RespValue::Status {
+                value: "OK".to_string(),
+            },
+        }
+    }
+}
+
+impl RespValue {
+    /// 服务端返回的错误, 连接或解析错误时返回None
+    pub fn from_error(err: &RedisError) -> Option<RespValue> {
+        let code = err.code()?;
+        Some(RespValue::Error {
+            code: code.to_string(),
+            message: err.detail().unwrap_or_default().to_string(),
+        })
+    }
+
+    /// 将查询结果转换为带类型的结构, 服务端错误也作为返回值
+    pub fn from_result(res: redis::RedisResult<Value>) -> redis::RedisResult<RespValue> {
+        match res {
+            Ok(value) => Ok(value.into()),
+            Err(err) => RespValue::from_error(&err).ok_or(err),
+        }
+    }
+}
+
+/// 从连接中读取一个RESP2回复
+///
+/// redis-rs会把包含错误的数组整体转换为错误, 终端需要保留EXEC等命令中其余的结果
+pub fn read_reply<R>(reader: &mut R) -> BoxFuture<'_, io::Result<RespValue>>
+where
+    R: AsyncBufRead + Unpin + Send,
+{
+    async move {
+        let mut line = Vec::new();
+        reader.read_until(b'\n', &mut line).await?;
+        if !line.ends_with(b"\r\n") {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        line.truncate(line.len() - 2);
+        let Some((&kind, rest)) = line.split_first() else {
+            return Err(invalid_data("回复为空"));
+        };
+        let text = String::from_utf8_lossy(rest).to_string();
+
+        match kind {
+            b'+' => Ok(RespValue::Status { value: text }),
+            b'-' => {
+                let (code, message) = text.split_once(' ').unwrap_or((&text, ""));
+                Ok(RespValue::Error {
+                    code: code.to_string(),
+                    message: message.to_string(),
+                })
+            }
+            b':' => Ok(RespValue::Integer {
+                value: parse_len(&text)?,
+            }),
+            b'$' => {
+                let len = parse_len(&text)?;
+                if len < 0 {
+                    return Ok(RespValue::Nil);
+                }
+                let mut data = vec![0; len as usize + 2];
+                reader.read_exact(&mut data).await?;
+                if !data.ends_with(b"\r\n") {
+                    return Err(invalid_data("bulk字符串缺少结尾的换行"));
+                }
+                data.truncate(len as usize);
+                Ok(bulk(data))
+            }
+            b'*' => {
+                let len = parse_len(&text)?;
+                if len < 0 {
+                    return Ok(RespValue::Nil);
+                }
+                let mut values = Vec::with_capacity((len as usize).min(1024));
+                for _ in 0..len {
+                    values.push(read_reply(reader).await?);
+                }
+                Ok(RespValue::Array { values })
+            }
+            _ => Err(invalid_data(format!("未知的回复类型: {}", kind as char))),
+        }
+    }
+    .boxed()
+}
+
+fn parse_len(text: &str) -> io::Result<i64> {
+    text.parse()
+        .map_err(|_| invalid_data(format!("回复中的长度格式错误: {text}")))
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+fn bulk(data: Vec<u8>) -> RespValue {
+    let repr = repr(&data);
+    match String::from_utf8(data) {
+        Ok(text) => RespValue::Bulk {
+            text,
+            repr,
+            bytes: None,
+        },
+        Err(err) => RespValue::Bulk {
+            text: repr.clone(),
+            repr,
+            bytes: Some(err.into_bytes()),
+        },
+    }
+}
+
+/// 与redis-cli(sdscatrepr)一致的转义, 不包含两边的引号
+pub fn repr(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len());
+    for &b in bytes {
+        match b {
+            b'\\' => out.push_str("\\\\"),
+            b'"' => out.push_str("\\\""),
+            b'\n' => out.push_str("\\n"),
+            b'\r' => out.push_str("\\r"),
+            b'\t' => out.push_str("\\t"),
+            0x07 => out.push_str("\\a"),
+            0x08 => out.push_str("\\b"),
+            b' '..=b'~' => out.push(b as char),
+            _ => out.push_str(&format!("\\x{b:02x}")),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::read_reply;
+    use crate::model::RespValue;
+
+    async fn read(mut input: &[u8]) -> std::io::Result<RespValue> {
+        read_reply(&mut input).await
+    }
+
+    fn bulk(text: &str) -> RespValue {
+        RespValue::Bulk {
+            text: text.to_string(),
+            repr: text.to_string(),
+            bytes: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn nil() {
+        assert_eq!(read(b"$-1\r\n").await.unwrap(), RespValue::Nil);
+        assert_eq!(read(b"*-1\r\n").await.unwrap(), RespValue::Nil);
+    }
+
+    #[tokio::test]
+    async fn binary_bulk() {
+        assert_eq!(
+            read(b"$3\r\na\xff\n\r\n").await.unwrap(),
+            RespValue::Bulk {
+                text: "a\\xff\\n".to_string(),
+                repr: "a\\xff\\n".to_string(),
+                bytes: Some(b"a\xff\n".to_vec()),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn exec_with_error() {
+        // EXEC中失败的命令不影响其余命令的结果
+        let value = read(b"*3\r\n+OK\r\n-WRONGTYPE Operation against a key\r\n$1\r\nv\r\n")
+            .await
+            .unwrap();
+        assert_eq!(
+            value,
+            RespValue::Array {
+                values: vec![
+                    RespValue::Status {
+                        value: "OK".to_string()
+                    },
+                    RespValue::Error {
+                        code: "WRONGTYPE".to_string(),
+                        message: "Operation against a key".to_string(),
+                    },
+                    bulk("v"),
+                ]
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn nested_array() {
+        assert_eq!(
+            read(b"*2\r\n:1\r\n*1\r\n$0\r\n\r\n").await.unwrap(),
+            RespValue::Array {
+                values: vec![
+                    RespValue::Integer { value: 1 },
+                    RespValue::Array {
+                        values: vec![bulk("")]
+                    },
+                ]
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn truncated() {
+        assert!(read(b"").await.is_err());
+        assert!(read(b"+OK").await.is_err());
+        assert!(read(b"$5\r\nab\r\n").await.is_err());
+        assert!(read(b"*2\r\n:1\r\n").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn invalid() {
+        assert!(read(b"%1\r\n").await.is_err());
+        assert!(read(b"$x\r\n").await.is_err());
+        assert!(read(b"$2\r\nabcd").await.is_err());
+    }
+}
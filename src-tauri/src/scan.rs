@@ -0,0 +1,82 @@
+use crate::{config::RedisConfig, error::Result, SharedConnection};
+
+/// 单次SCAN建议返回的键数量
+pub const SCAN_COUNT: usize = 500;
+
+/// 按批次扫描匹配的键, 集群模式下依次扫描每个主节点
+pub struct KeyScanner {
+    nodes: Vec<SharedConnection>,
+    node: usize,
+    cursor: u64,
+    pattern: String,
+    count: usize,
+}
+
+impl KeyScanner {
+    pub async fn new(
+        config: &RedisConfig,
+        con: &SharedConnection,
+        pattern: &str,
+        count: usize,
+    ) -> Result<KeyScanner> {
+        Ok(KeyScanner {
+            nodes: con.nodes(config).await?,
+            node: 0,
+            cursor: 0,
+            pattern: pattern.to_string(),
+            count,
+        })
+    }
+
+    /// 已扫描完成的节点数和节点总数
+    pub fn nodes(&self) -> (usize, usize) {
+        (self.node, self.nodes.len())
+    }
+
+    /// 返回下一批键以及键所在节点的连接, 扫描完成时返回None
+    ///
+    /// 单次SCAN可能返回空的批次, 这里会继续扫描直到拿到键或扫描结束
+    pub async fn next_batch(&mut self) -> Result<Option<(SharedConnection, Vec<String>)>> {
+        while let Some(node) = self.nodes.get(self.node) {
+            let mut con = node.clone();
+            let (cursor, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+                .arg(self.cursor)
+                .arg("MATCH")
+                .arg(&self.pattern)
+                .arg("COUNT")
+                .arg(self.count)
+                .query_async(&mut con)
+                .await?;
+
+            if cursor == 0 {
+                self.node += 1;
+            }
+            self.cursor = cursor;
+
+            if !keys.is_empty() {
+                return Ok(Some((con, keys)));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// 统计匹配的键数量, 并返回最先扫描到的limit个键作为预览
+pub async fn preview_keys(
+    config: &RedisConfig,
+    con: &SharedConnection,
+    pattern: &str,
+    limit: usize,
+) -> Result<(u64, Vec<String>)> {
+    let mut count = 0;
+    let mut sample = vec![];
+    let mut scanner = KeyScanner::new(config, con, pattern, SCAN_COUNT).await?;
+    while let Some((_, keys)) = scanner.next_batch().await? {
+        count += keys.len() as u64;
+        let remaining = limit.saturating_sub(sample.len());
+        sample.extend(keys.into_iter().take(remaining));
+    }
+
+    Ok((count, sample))
+}
@@ -0,0 +1,47 @@
+//! 按连接保存在本地的lua脚本
+
+use crate::{error::Result, model::SavedScript, store::JsonStore};
+use chrono::Local;
+use std::path::PathBuf;
+
+#[derive(Debug, Default, Clone)]
+pub struct ScriptStore(JsonStore<SavedScript>);
+
+impl ScriptStore {
+    /// dir为空时只保存在内存中
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Self(JsonStore::new(dir))
+    }
+
+    /// 按名称排序
+    pub fn list(&self, id: &str) -> Vec<SavedScript> {
+        let mut scripts = self.0.read(id, |list| list.to_vec());
+        scripts.sort_by(|a, b| a.name.cmp(&b.name));
+        scripts
+    }
+
+    /// id为0或不存在时新建, 否则覆盖
+    pub fn save(&self, id: &str, mut script: SavedScript) -> Result<SavedScript> {
+        script.updated_at = Local::now().timestamp_millis();
+        self.0.update(id, |list| {
+            if let Some(saved) = list
+                .iter_mut()
+                .find(|s| script.id != 0 && s.id == script.id)
+            {
+                *saved = script.clone();
+            } else {
+                script.id = list.iter().map(|s| s.id).max().unwrap_or_default() + 1;
+                list.push(script.clone());
+            }
+            script
+        })
+    }
+
+    pub fn delete(&self, id: &str, script_id: u64) -> Result<bool> {
+        self.0.update(id, |list| {
+            let len = list.len();
+            list.retain(|s| s.id != script_id);
+            list.len() != len
+        })
+    }
+}
@@ -0,0 +1,74 @@
+//! 终端, 调试器等使用独立连接的会话, 按会话id保存
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+/// 已打开的会话, 同时记录会话所属的连接id, 断开连接时不需要等待会话空闲
+pub struct Sessions<S> {
+    next_id: Arc<AtomicU64>,
+    sessions: Arc<Mutex<HashMap<String, (String, S)>>>,
+}
+
+impl<S> Default for Sessions<S> {
+    fn default() -> Self {
+        Self {
+            next_id: Default::default(),
+            sessions: Default::default(),
+        }
+    }
+}
+
+impl<S> Clone for Sessions<S> {
+    fn clone(&self) -> Self {
+        Self {
+            next_id: self.next_id.clone(),
+            sessions: self.sessions.clone(),
+        }
+    }
+}
+
+impl<S: Clone> Sessions<S> {
+    /// 生成新的会话id, 例如`term-1`
+    pub fn next_id(&self, prefix: &str) -> String {
+        format!(
+            "{prefix}-{}",
+            self.next_id.fetch_add(1, Ordering::Relaxed) + 1
+        )
+    }
+
+    /// 保存连接id对应的会话
+    pub fn add(&self, session_id: String, id: String, session: S) {
+        self.sessions
+            .lock()
+            .unwrap()
+            .insert(session_id, (id, session));
+    }
+
+    pub fn get(&self, session_id: &str) -> Option<S> {
+        let sessions = self.sessions.lock().unwrap();
+        sessions.get(session_id).map(|(_, session)| session.clone())
+    }
+
+    pub fn values(&self) -> Vec<S> {
+        let sessions = self.sessions.lock().unwrap();
+        sessions
+            .values()
+            .map(|(_, session)| session.clone())
+            .collect()
+    }
+
+    pub fn remove(&self, session_id: &str) -> bool {
+        self.sessions.lock().unwrap().remove(session_id).is_some()
+    }
+
+    /// 关闭连接的所有会话, id为None时关闭全部会话; 正在执行的命令结束后连接随之断开
+    pub fn remove_by_id(&self, id: Option<&str>) {
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.retain(|_, (owner, _)| id.is_some_and(|id| id != owner.as_str()));
+    }
+}
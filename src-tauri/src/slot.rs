@@ -0,0 +1,77 @@
+//! 集群模式下键所在的哈希槽
+
+/// 集群的哈希槽数量
+pub const SLOT_COUNT: u16 = 16384;
+
+/// 计算键所在的槽, 键中包含`{tag}`时只使用tag计算
+pub fn key_slot(key: &[u8]) -> u16 {
+    crc16(hash_tag(key)) % SLOT_COUNT
+}
+
+fn hash_tag(key: &[u8]) -> &[u8] {
+    if let Some(start) = key.iter().position(|b| *b == b'{') {
+        if let Some(len) = key[start + 1..].iter().position(|b| *b == b'}') {
+            // 空的{}不作为tag
+            if len > 0 {
+                return &key[start + 1..start + 1 + len];
+            }
+        }
+    }
+    key
+}
+
+/// CRC16-CCITT(XMODEM), 与redis集群使用的算法一致
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for byte in data {
+        crc ^= (*byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{crc16, hash_tag, key_slot};
+
+    #[test]
+    fn crc16_vector() {
+        // 集群规范中给出的校验值
+        assert_eq!(crc16(b"123456789"), 0x31c3);
+        assert_eq!(key_slot(b"123456789"), 0x31c3);
+    }
+
+    #[test]
+    fn known_slots() {
+        assert_eq!(key_slot(b"foo"), 12182);
+        assert_eq!(key_slot(b"bar"), 5061);
+        assert_eq!(key_slot(b""), 0);
+    }
+
+    #[test]
+    fn hash_tags() {
+        assert_eq!(
+            key_slot(b"{user1000}.following"),
+            key_slot(b"{user1000}.followers")
+        );
+        assert_eq!(key_slot(b"{user1000}.following"), key_slot(b"user1000"));
+        assert_eq!(hash_tag(b"foo{bar}{zap}"), b"bar");
+        assert_eq!(hash_tag(b"foo{{bar}}zap"), b"{bar");
+    }
+
+    #[test]
+    fn hash_tag_edge_cases() {
+        // 空的{}或没有}时使用整个键
+        assert_eq!(hash_tag(b"foo{}{bar}"), b"foo{}{bar}");
+        assert_eq!(hash_tag(b"{}"), b"{}");
+        assert_eq!(hash_tag(b"{"), b"{");
+        assert_eq!(hash_tag(b"foo{bar"), b"foo{bar");
+        assert_eq!(hash_tag(b"}{"), b"}{");
+    }
+}
@@ -0,0 +1,139 @@
+//! 通过gedis修改的键在修改前的值, 按连接保存在本地
+
+use crate::{
+    error::Result,
+    model::{KeyRecord, Snapshot, SnapshotSummary},
+    store::JsonStore,
+    value::{len_cmd, read_record},
+};
+use chrono::Local;
+use redis::{aio::ConnectionLike, AsyncCommands, RedisResult};
+use std::path::PathBuf;
+use tracing::warn;
+
+/// 每个连接的历史数据最多占用的字节数
+const MAX_BYTES: usize = 16 * 1024 * 1024;
+/// 每个键最多保留的版本数量
+const MAX_VERSIONS: usize = 50;
+/// 单个版本最多占用的字节数, 超过时不保存
+const MAX_SNAPSHOT_BYTES: usize = 1024 * 1024;
+/// 集合类型最多保存的元素数量, 超过时不读取值
+const MAX_SNAPSHOT_ELEMENTS: u64 = 10_000;
+
+/// 按连接保存的历史版本, 超过上限时丢弃最早的版本
+#[derive(Debug, Default, Clone)]
+pub struct SnapshotStore(JsonStore<Snapshot>);
+
+impl SnapshotStore {
+    /// dir为空时只保存在内存中
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Self(JsonStore::new(dir))
+    }
+
+    /// 读取键的当前值并保存为新版本, 键不存在, 值过大或读取失败时忽略
+    pub async fn capture<C>(&self, con: &mut C, id: &str, db: u8, op: &str, key: &str)
+    where
+        C: ConnectionLike + Send,
+    {
+        match too_large(con, key).await {
+            Ok(false) => {}
+            Ok(true) => {
+                warn!(key, "值过大, 不保存历史版本");
+                return;
+            }
+            Err(err) => {
+                warn!(?err, key, "读取历史版本失败");
+                return;
+            }
+        }
+
+        match read_record(con, key).await {
+            Ok(Some(record)) => {
+                if let Err(err) = self.push(id, db, op, record) {
+                    warn!(err = err.message(), key, "保存历史版本失败");
+                }
+            }
+            Ok(None) => {}
+            Err(err) => warn!(?err, key, "读取历史版本失败"),
+        }
+    }
+
+    pub fn push(&self, id: &str, db: u8, op: &str, record: KeyRecord) -> Result<()> {
+        let bytes = serde_json::to_vec(&record)?.len();
+        if bytes > MAX_SNAPSHOT_BYTES {
+            warn!(key = record.key, bytes, "值过大, 不保存历史版本");
+            return Ok(());
+        }
+
+        self.0.update_later(id, |list| {
+            let snapshot = Snapshot {
+                id: list.last().map(|s| s.id).unwrap_or_default() + 1,
+                db,
+                op: op.to_string(),
+                created_at: Local::now().timestamp_millis(),
+                bytes,
+                record,
+            };
+
+            let same_key = |s: &Snapshot| s.db == db && s.record.key == snapshot.record.key;
+            if list.iter().filter(|s| same_key(s)).count() >= MAX_VERSIONS {
+                if let Some(index) = list.iter().position(same_key) {
+                    list.remove(index);
+                }
+            }
+            list.push(snapshot);
+            while list.iter().map(|s| s.bytes).sum::<usize>() > MAX_BYTES {
+                list.remove(0);
+            }
+        });
+        Ok(())
+    }
+
+    /// 键的所有版本, 最新的在前
+    pub fn list(&self, id: &str, db: u8, key: &str) -> Vec<SnapshotSummary> {
+        self.0.read(id, |list| {
+            list.iter()
+                .rev()
+                .filter(|s| s.db == db && s.record.key == key)
+                .map(Snapshot::summary)
+                .collect()
+        })
+    }
+
+    pub fn get(&self, id: &str, version: u64) -> Option<Snapshot> {
+        self.0
+            .read(id, |list| list.iter().find(|s| s.id == version).cloned())
+    }
+}
+
+/// 读取完整的值之前按长度判断是否过大, 避免对大键执行LRANGE, XRANGE等O(N)命令
+async fn too_large<C>(con: &mut C, key: &str) -> RedisResult<bool>
+where
+    C: ConnectionLike + Send,
+{
+    let key_type: String = con.key_type(key).await?;
+    let Some(cmd) = len_cmd(&key_type, key) else {
+        return Ok(false);
+    };
+    let len: u64 = cmd.query_async(con).await?;
+
+    Ok(match key_type.as_str() {
+        "string" => len > MAX_SNAPSHOT_BYTES as u64,
+        _ => len > MAX_SNAPSHOT_ELEMENTS,
+    })
+}
+
+impl Snapshot {
+    pub fn summary(&self) -> SnapshotSummary {
+        SnapshotSummary {
+            id: self.id,
+            db: self.db,
+            key: self.record.key.clone(),
+            op: self.op.clone(),
+            created_at: self.created_at,
+            r#type: self.record.value.type_name().to_string(),
+            size: self.record.value.len(),
+            bytes: self.bytes,
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! 按连接保存在本地的数据, 每个连接对应目录下的一个json文件
+
+use crate::error::Result;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+use tracing::warn;
+
+/// 第一次访问连接时从文件加载, 修改后整体写回文件
+#[derive(Debug)]
+pub struct JsonStore<T> {
+    dir: Option<PathBuf>,
+    connections: Arc<Mutex<HashMap<String, Vec<T>>>>,
+    /// 保证写文件按顺序进行
+    writing: Arc<Mutex<()>>,
+}
+
+impl<T> Default for JsonStore<T> {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            connections: Default::default(),
+            writing: Default::default(),
+        }
+    }
+}
+
+impl<T> Clone for JsonStore<T> {
+    fn clone(&self) -> Self {
+        Self {
+            dir: self.dir.clone(),
+            connections: self.connections.clone(),
+            writing: self.writing.clone(),
+        }
+    }
+}
+
+impl<T: Serialize + DeserializeOwned + Clone + Send + 'static> JsonStore<T> {
+    /// dir为空时只保存在内存中
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Self {
+            dir,
+            connections: Default::default(),
+            writing: Default::default(),
+        }
+    }
+
+    pub fn read<R>(&self, id: &str, f: impl FnOnce(&[T]) -> R) -> R {
+        let mut connections = self.connections.lock().unwrap();
+        f(self.load(&mut connections, id))
+    }
+
+    /// 修改连接的数据并写回文件, 返回写入文件的结果
+    pub fn update<R>(&self, id: &str, f: impl FnOnce(&mut Vec<T>) -> R) -> Result<R> {
+        let res = self.modify(id, f);
+        let _writing = self.writing.lock().unwrap();
+        self.save(id)?;
+        Ok(res)
+    }
+
+    /// 修改连接的数据, 不等待写回文件, 写入失败时只记录日志
+    pub fn update_later<R>(&self, id: &str, f: impl FnOnce(&mut Vec<T>) -> R) -> R {
+        let res = self.modify(id, f);
+        self.save_later(id);
+        res
+    }
+
+    fn modify<R>(&self, id: &str, f: impl FnOnce(&mut Vec<T>) -> R) -> R {
+        let mut connections = self.connections.lock().unwrap();
+        f(self.load(&mut connections, id))
+    }
+
+    fn load<'a>(&self, connections: &'a mut HashMap<String, Vec<T>>, id: &str) -> &'a mut Vec<T> {
+        connections.entry(id.to_string()).or_insert_with(|| {
+            let Some(path) = self.path(id) else {
+                return vec![];
+            };
+            match fs::read(&path) {
+                Ok(data) => serde_json::from_slice(&data).unwrap_or_else(|err| {
+                    warn!(?err, ?path, "本地数据文件格式错误");
+                    vec![]
+                }),
+                Err(_) => vec![],
+            }
+        })
+    }
+
+    /// 文件可能有十几MB, 在后台线程中序列化并写入, 后执行的写入总是使用最新的数据
+    fn save_later(&self, id: &str) {
+        if self.dir.is_none() {
+            return;
+        }
+
+        let store = self.clone();
+        let id = id.to_string();
+        tauri::async_runtime::spawn_blocking(move || {
+            let _writing = store.writing.lock().unwrap();
+            if let Err(err) = store.save(&id) {
+                warn!(err = err.message(), id, "写入本地数据文件失败");
+            }
+        });
+    }
+
+    /// 只在复制数据时持有锁; 先写入临时文件再替换, 中途退出不会留下不完整的文件
+    fn save(&self, id: &str) -> Result<()> {
+        let (Some(dir), Some(path)) = (&self.dir, self.path(id)) else {
+            return Ok(());
+        };
+        let Some(list) = self.connections.lock().unwrap().get(id).cloned() else {
+            return Ok(());
+        };
+        let data = serde_json::to_vec(&list)?;
+
+        fs::create_dir_all(dir)?;
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, data)?;
+        fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    /// 连接id只保留字母数字作为文件名
+    fn path(&self, id: &str) -> Option<PathBuf> {
+        let name: String = id
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        Some(self.dir.as_ref()?.join(format!("{name}.json")))
+    }
+}
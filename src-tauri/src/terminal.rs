@@ -0,0 +1,226 @@
+//! 终端会话, 每个终端标签页使用独立的连接
+//!
+//! SELECT, MULTI, AUTH等命令只影响会话自身的连接, 不会干扰浏览键时使用的共享连接
+
+use crate::{
+    config::RedisConfig,
+    error::Result,
+    model::{RespValue, TerminalSessionInfo},
+    resp::read_reply,
+    session::Sessions,
+    CmdLog, History, RedisConnection,
+};
+use anyhow::anyhow;
+use chrono::Local;
+use std::{ops::Deref, sync::Arc, time::Duration};
+use tokio::{
+    io::{AsyncWriteExt, BufStream},
+    net::TcpStream,
+    sync::Mutex,
+};
+
+/// 超时后回复可能还会到达, 连接不能继续使用
+const READ_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 单机模式直接读写原始回复, 保留EXEC等数组回复中错误以外的结果;
+/// 集群模式需要按槽位路由, 仍使用redis-rs的连接
+enum TerminalConnection {
+    Raw(BufStream<TcpStream>),
+    Cluster(RedisConnection),
+}
+
+impl TerminalConnection {
+    async fn open(config: &RedisConfig) -> Result<Self> {
+        if config.cluster {
+            return Ok(Self::Cluster(RedisConnection::new(config).await?));
+        }
+
+        let stream = TcpStream::connect((config.host.as_str(), config.port)).await?;
+        let mut con = Self::Raw(BufStream::new(stream));
+        if let Some(password) = config.password.as_ref().filter(|p| !p.is_empty()) {
+            let mut cmd = redis::cmd("AUTH");
+            if let Some(user) = config.username.as_ref().filter(|u| !u.is_empty()) {
+                cmd.arg(user);
+            }
+            cmd.arg(password);
+            if let RespValue::Error { code, message } = con.query(&cmd).await? {
+                return Err(anyhow!("{code} {message}").into());
+            }
+        }
+        Ok(con)
+    }
+
+    /// 返回错误时连接已不可用, 服务端错误作为返回值
+    async fn query(&mut self, cmd: &redis::Cmd) -> Result<RespValue> {
+        match self {
+            Self::Raw(stream) => {
+                let query = async {
+                    stream.write_all(&cmd.get_packed_command()).await?;
+                    stream.flush().await?;
+                    read_reply(stream).await
+                };
+                match tokio::time::timeout(READ_TIMEOUT, query).await {
+                    Ok(value) => Ok(value?),
+                    Err(_) => Err(anyhow!("等待回复超时, 连接已断开").into()),
+                }
+            }
+            Self::Cluster(con) => Ok(RespValue::from_result(cmd.query_async(con).await)?),
+        }
+    }
+}
+
+pub struct TerminalSession {
+    con: TerminalConnection,
+    config: RedisConfig,
+    info: TerminalSessionInfo,
+}
+
+impl TerminalSession {
+    /// 打开独立的连接并选择数据库
+    pub async fn open(config: &RedisConfig, db: u8, history: &History) -> Result<Self> {
+        let mut con = TerminalConnection::open(config).await?;
+        if db != 0 && !config.cluster {
+            let mut cmd = redis::cmd("select");
+            cmd.arg(db).log(history.0.clone(), config);
+            if let RespValue::Error { code, message } = con.query(&cmd).await? {
+                return Err(anyhow!("{code} {message}").into());
+            }
+        }
+
+        let user = config
+            .username
+            .clone()
+            .filter(|user| !user.is_empty())
+            .unwrap_or_else(|| "default".to_string());
+        Ok(Self {
+            con,
+            config: config.clone(),
+            info: TerminalSessionInfo {
+                session_id: String::new(),
+                id: config.id.clone(),
+                db: if config.cluster { 0 } else { db },
+                transaction: false,
+                queued: 0,
+                user,
+                started_at: Local::now().timestamp_millis(),
+            },
+        })
+    }
+
+    pub fn info(&self) -> &TerminalSessionInfo {
+        &self.info
+    }
+
+    /// 执行命令, 根据命令和返回值更新会话状态; 返回错误时连接已不可用
+    pub async fn execute(&mut self, args: Vec<Vec<u8>>, history: &History) -> Result<RespValue> {
+        let name = String::from_utf8_lossy(&args[0]).to_uppercase();
+        if let Some(message) = unsupported(&name, &args[1..], self.config.cluster) {
+            return Ok(RespValue::Error {
+                code: "ERR".to_string(),
+                message: message.to_string(),
+            });
+        }
+
+        let mut cmd = redis::Cmd::new();
+        for arg in &args {
+            cmd.arg(arg);
+        }
+        cmd.log(history.0.clone(), &self.config);
+        let value = self.con.query(&cmd).await?;
+
+        self.update(&name, &args[1..], &value);
+        Ok(value)
+    }
+
+    /// 事务中的命令只是入队, 不会改变会话状态
+    fn update(&mut self, name: &str, args: &[Vec<u8>], value: &RespValue) {
+        let info = &mut self.info;
+        let ok = matches!(value, RespValue::Status { value } if value == "OK");
+
+        match name {
+            // EXEC和DISCARD无论成功与否都会结束事务
+            "EXEC" | "DISCARD" => {
+                info.transaction = false;
+                info.queued = 0;
+            }
+            // RESET不会入队, 直接结束事务并恢复连接的默认状态
+            "RESET" => {
+                info.transaction = false;
+                info.queued = 0;
+                info.db = 0;
+                info.user = "default".to_string();
+            }
+            _ if info.transaction => {
+                if matches!(value, RespValue::Status { value } if value == "QUEUED") {
+                    info.queued += 1;
+                }
+            }
+            "MULTI" if ok => info.transaction = true,
+            "SELECT" if ok => {
+                if let Some(db) = args
+                    .first()
+                    .and_then(|db| String::from_utf8_lossy(db).parse().ok())
+                {
+                    info.db = db;
+                }
+            }
+            // AUTH password 或 AUTH username password
+            "AUTH" if ok => {
+                info.user = match args {
+                    [user, _] => String::from_utf8_lossy(user).to_string(),
+                    _ => "default".to_string(),
+                };
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 终端每条命令只读取一个回复, 不支持返回多个回复, 不返回回复或切换协议的命令
+fn unsupported(name: &str, args: &[Vec<u8>], cluster: bool) -> Option<&'static str> {
+    let sub = |value: &[u8]| {
+        args.first()
+            .is_some_and(|arg| arg.eq_ignore_ascii_case(value))
+    };
+    match name {
+        "SUBSCRIBE" | "PSUBSCRIBE" | "SSUBSCRIBE" | "UNSUBSCRIBE" | "PUNSUBSCRIBE"
+        | "SUNSUBSCRIBE" => Some("终端不支持订阅模式"),
+        "MONITOR" | "SYNC" | "PSYNC" => Some("终端不支持持续返回数据的命令"),
+        "CLIENT" if sub(b"REPLY") => Some("终端不支持CLIENT REPLY"),
+        "HELLO"
+            if args
+                .first()
+                .is_some_and(|version| version.as_slice() != b"2") =>
+        {
+            Some("终端只支持RESP2协议")
+        }
+        "MULTI" | "WATCH" if cluster => Some("集群模式下终端不支持事务"),
+        // 集群连接会在多个节点间切换, 认证和连接名不会作用于后续命令所在的节点
+        "AUTH" if cluster => Some("集群模式下终端不支持AUTH"),
+        "CLIENT" if cluster && sub(b"SETNAME") => Some("集群模式下终端不支持CLIENT SETNAME"),
+        _ => None,
+    }
+}
+
+/// 已打开的终端会话
+#[derive(Default, Clone)]
+pub struct TerminalState(Sessions<Arc<Mutex<TerminalSession>>>);
+
+impl Deref for TerminalState {
+    type Target = Sessions<Arc<Mutex<TerminalSession>>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl TerminalState {
+    /// 保存会话并返回会话信息
+    pub fn insert(&self, mut session: TerminalSession) -> TerminalSessionInfo {
+        let session_id = self.next_id("term");
+        session.info.session_id = session_id.clone();
+        let info = session.info.clone();
+        self.add(session_id, info.id.clone(), Arc::new(Mutex::new(session)));
+        info
+    }
+}
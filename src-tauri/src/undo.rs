@@ -0,0 +1,134 @@
+use crate::{dump::KeyDump, model::UndoSummary};
+use chrono::Local;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+/// 每个连接最多保留的撤销记录数量
+const MAX_ENTRIES: usize = 20;
+/// 每个连接的撤销数据最多占用的字节数
+const MAX_BYTES: usize = 64 * 1024 * 1024;
+/// 单条撤销记录最多保存的键数量
+const MAX_KEYS: usize = 10_000;
+/// 撤销记录摘要中展示的键数量
+const SUMMARY_KEYS: usize = 10;
+
+/// 一次破坏性操作之前保存的键
+#[derive(Debug, Clone)]
+pub struct UndoEntry {
+    pub id: u64,
+    /// 操作名称, 例如del, set
+    pub op: String,
+    pub db: u8,
+    /// 保存时间(unix时间戳, 毫秒), 恢复时据此计算剩余过期时间
+    pub created_at: i64,
+    pub keys: Vec<(String, KeyDump)>,
+    pub bytes: usize,
+    /// 超过上限时只保存了部分键
+    pub truncated: bool,
+}
+
+impl UndoEntry {
+    pub fn new(op: &str, db: u8) -> Self {
+        Self {
+            id: 0,
+            op: op.to_string(),
+            db,
+            created_at: Local::now().timestamp_millis(),
+            keys: vec![],
+            bytes: 0,
+            truncated: false,
+        }
+    }
+
+    /// 是否还可以继续保存键
+    pub fn is_full(&self) -> bool {
+        self.truncated
+    }
+
+    pub fn push(&mut self, key: String, dump: KeyDump) {
+        let size = key.len() + dump.payload.len();
+        if self.truncated || self.keys.len() >= MAX_KEYS || self.bytes + size > MAX_BYTES {
+            self.truncated = true;
+            return;
+        }
+
+        self.bytes += size;
+        self.keys.push((key, dump));
+    }
+
+    pub fn summary(&self) -> UndoSummary {
+        UndoSummary {
+            id: self.id,
+            op: self.op.clone(),
+            db: self.db,
+            created_at: self.created_at,
+            count: self.keys.len(),
+            bytes: self.bytes,
+            truncated: self.truncated,
+            keys: self
+                .keys
+                .iter()
+                .take(SUMMARY_KEYS)
+                .map(|(key, _)| key.clone())
+                .collect(),
+        }
+    }
+}
+
+/// 按连接保存的撤销记录, 超过数量或大小上限时丢弃最早的记录
+#[derive(Debug, Default, Clone)]
+pub struct UndoState {
+    next_id: Arc<AtomicU64>,
+    entries: Arc<Mutex<HashMap<String, VecDeque<UndoEntry>>>>,
+}
+
+impl UndoState {
+    /// 保存撤销记录, 没有键时忽略
+    pub fn push(&self, id: &str, mut entry: UndoEntry) {
+        if entry.keys.is_empty() {
+            return;
+        }
+        entry.id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+
+        let mut entries = self.entries.lock().unwrap();
+        let list = entries.entry(id.to_string()).or_default();
+        list.push_back(entry);
+        while list.len() > MAX_ENTRIES || list.iter().map(|e| e.bytes).sum::<usize>() > MAX_BYTES {
+            list.pop_front();
+        }
+    }
+
+    /// 最新的记录在前
+    pub fn list(&self, id: &str) -> Vec<UndoSummary> {
+        self.entries
+            .lock()
+            .unwrap()
+            .get(id)
+            .map(|list| list.iter().rev().map(UndoEntry::summary).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn take_last(&self, id: &str) -> Option<UndoEntry> {
+        self.entries.lock().unwrap().get_mut(id)?.pop_back()
+    }
+
+    pub fn take(&self, id: &str, undo_id: u64) -> Option<UndoEntry> {
+        let mut entries = self.entries.lock().unwrap();
+        let list = entries.get_mut(id)?;
+        let index = list.iter().position(|entry| entry.id == undo_id)?;
+        list.remove(index)
+    }
+
+    /// 恢复失败时放回原来的位置
+    pub fn put_back(&self, id: &str, entry: UndoEntry) {
+        let mut entries = self.entries.lock().unwrap();
+        let list = entries.entry(id.to_string()).or_default();
+        let index = list.partition_point(|e| e.id < entry.id);
+        list.insert(index, entry);
+    }
+}
@@ -0,0 +1,147 @@
+use crate::model::{KeyRecord, KeyValue, StreamEntry, Z};
+use redis::{aio::ConnectionLike, AsyncCommands, AsyncIter, Cmd, RedisResult};
+use std::collections::BTreeMap;
+
+/// 读取键的类型, 过期时间以及完整的值, 键不存在时返回None
+///
+/// 值按utf8字符串读取, 包含非utf8编码的值时返回TypeError
+pub async fn read_record<C>(con: &mut C, key: &str) -> RedisResult<Option<KeyRecord>>
+where
+    C: ConnectionLike + Send,
+{
+    let (typ, pttl): (String, i64) = redis::pipe()
+        .key_type(key)
+        .pttl(key)
+        .query_async(con)
+        .await?;
+
+    let value = match typ.as_str() {
+        "string" => KeyValue::String(con.get(key).await?),
+        "list" => KeyValue::List(con.lrange(key, 0, -1).await?),
+        "set" => {
+            let mut iter: AsyncIter<'_, String> = con.sscan(key).await?;
+            let mut values = vec![];
+            while let Some(val) = iter.next_item().await {
+                values.push(val);
+            }
+            KeyValue::Set(values)
+        }
+        "zset" => {
+            let data: Vec<(String, f64)> = con.zrange_withscores(key, 0, -1).await?;
+            KeyValue::ZSet(data.into_iter().map(|d| Z::new(d.1, d.0)).collect())
+        }
+        "hash" => {
+            let mut iter: AsyncIter<'_, (String, String)> = con.hscan(key).await?;
+            let mut values = BTreeMap::new();
+            while let Some((field, value)) = iter.next_item().await {
+                values.insert(field, value);
+            }
+            KeyValue::Hash(values)
+        }
+        "stream" => {
+            let reply: redis::streams::StreamRangeReply = con.xrange_all(key).await?;
+            let entries = reply
+                .ids
+                .into_iter()
+                .map(|id| StreamEntry {
+                    id: id.id,
+                    fields: id
+                        .map
+                        .into_iter()
+                        .map(|m| (m.0, redis::from_redis_value(&m.1).unwrap_or_default()))
+                        .collect(),
+                })
+                .collect();
+            KeyValue::Stream(entries)
+        }
+        // none: 键不存在或已过期
+        _ => return Ok(None),
+    };
+
+    Ok(Some(KeyRecord {
+        key: key.to_string(),
+        pttl,
+        value,
+    }))
+}
+
+/// 生成重建键所需的命令: 先删除旧值, 写入新值后恢复过期时间
+pub fn record_cmds(record: &KeyRecord) -> Vec<Cmd> {
+    let key = &record.key;
+    let mut cmds = vec![];
+
+    let mut del = redis::cmd("DEL");
+    del.arg(key);
+    cmds.push(del);
+
+    match &record.value {
+        KeyValue::String(value) => {
+            let mut cmd = redis::cmd("SET");
+            cmd.arg(key).arg(value);
+            cmds.push(cmd);
+        }
+        KeyValue::List(values) if !values.is_empty() => {
+            let mut cmd = redis::cmd("RPUSH");
+            cmd.arg(key).arg(values);
+            cmds.push(cmd);
+        }
+        KeyValue::Set(values) if !values.is_empty() => {
+            let mut cmd = redis::cmd("SADD");
+            cmd.arg(key).arg(values);
+            cmds.push(cmd);
+        }
+        KeyValue::ZSet(values) if !values.is_empty() => {
+            let mut cmd = redis::cmd("ZADD");
+            cmd.arg(key);
+            for z in values {
+                cmd.arg(z.score).arg(&z.member);
+            }
+            cmds.push(cmd);
+        }
+        KeyValue::Hash(values) if !values.is_empty() => {
+            let mut cmd = redis::cmd("HSET");
+            cmd.arg(key);
+            for (field, value) in values {
+                cmd.arg(field).arg(value);
+            }
+            cmds.push(cmd);
+        }
+        KeyValue::Stream(entries) => {
+            for entry in entries {
+                let mut cmd = redis::cmd("XADD");
+                cmd.arg(key).arg(&entry.id);
+                for (field, value) in &entry.fields {
+                    cmd.arg(field).arg(value);
+                }
+                cmds.push(cmd);
+            }
+        }
+        // 空集合在redis中不存在, 无需写入
+        _ => {}
+    }
+
+    if record.pttl > 0 {
+        let mut cmd = redis::cmd("PEXPIRE");
+        cmd.arg(key).arg(record.pttl);
+        cmds.push(cmd);
+    }
+
+    cmds
+}
+
+/// 按类型返回元素数量的命令, 字符串为字节长度
+pub fn len_cmd(key_type: &str, key: &str) -> Option<Cmd> {
+    let name = match key_type {
+        "string" => "STRLEN",
+        "list" => "LLEN",
+        "set" => "SCARD",
+        "zset" => "ZCARD",
+        "hash" => "HLEN",
+        "stream" => "XLEN",
+        _ => return None,
+    };
+
+    let mut cmd = redis::cmd(name);
+    cmd.arg(key);
+    Some(cmd)
+}
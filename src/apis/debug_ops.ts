@@ -0,0 +1,28 @@
+import { DebugCommand, DebugReply, DebugRequest, DebugSessionInfo } from '@/types/redis'
+import { invoke } from '@tauri-apps/api'
+
+/** 调试事件名称, 每次调试器输出时发送 */
+export const DEBUG_EVENT = 'debug'
+
+export function debugStart(id: string, db: number, request: DebugRequest) {
+  return invoke<DebugReply>('debug_start', { id, db, request })
+}
+
+export function debugCommand(sessionId: string, command: DebugCommand) {
+  return invoke<DebugReply>('debug_command', { sessionId, command })
+}
+
+export function debugSessions() {
+  return invoke<DebugSessionInfo[]>('debug_sessions')
+}
+
+export function debugStop(sessionId: string) {
+  return invoke<boolean>('debug_stop', { sessionId })
+}
+
+export default {
+  debugStart,
+  debugCommand,
+  debugSessions,
+  debugStop,
+}
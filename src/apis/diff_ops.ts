@@ -0,0 +1,20 @@
+import { DiffOptions, KeyComparison, KeyLocation, SyncOptions } from '@/types/redis'
+import { invoke } from '@tauri-apps/api'
+
+export function diffKeys(options: DiffOptions) {
+  return invoke<string>('diff_keys', { options })
+}
+
+export function syncKeys(options: SyncOptions) {
+  return invoke<string>('sync_keys', { options })
+}
+
+export function compareKeys(source: KeyLocation, target: KeyLocation) {
+  return invoke<KeyComparison>('compare_keys', { source, target })
+}
+
+export default {
+  diffKeys,
+  syncKeys,
+  compareKeys,
+}
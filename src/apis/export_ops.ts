@@ -0,0 +1,10 @@
+import { ExportOptions, ExportReport } from '@/types/redis'
+import { invoke } from '@tauri-apps/api'
+
+export function exportKeys(id: string, db: number, options: ExportOptions) {
+  return invoke<ExportReport>('export_keys', { id, db, options })
+}
+
+export default {
+  exportKeys,
+}
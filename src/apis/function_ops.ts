@@ -0,0 +1,35 @@
+import { FcallRequest, FunctionLibrary, FunctionRestorePolicy, RespValue } from '@/types/redis'
+import { invoke } from '@tauri-apps/api'
+
+export function functionList(id: string, pattern?: string, withCode?: boolean) {
+  return invoke<FunctionLibrary[]>('function_list', { id, pattern, withCode })
+}
+
+export function functionLoad(id: string, code: string, replace?: boolean) {
+  return invoke<string>('function_load', { id, code, replace })
+}
+
+export function functionDelete(id: string, library: string) {
+  return invoke<void>('function_delete', { id, library })
+}
+
+export function functionCall(id: string, db: number, request: FcallRequest) {
+  return invoke<RespValue>('function_call', { id, db, request })
+}
+
+export function functionDump(id: string, path: string) {
+  return invoke<number>('function_dump', { id, path })
+}
+
+export function functionRestore(id: string, path: string, policy?: FunctionRestorePolicy) {
+  return invoke<void>('function_restore', { id, path, policy })
+}
+
+export default {
+  functionList,
+  functionLoad,
+  functionDelete,
+  functionCall,
+  functionDump,
+  functionRestore,
+}
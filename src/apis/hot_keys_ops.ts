@@ -0,0 +1,15 @@
+import { HotKeyOptions, HotKeySupport } from '@/types/redis'
+import { invoke } from '@tauri-apps/api'
+
+export function hotKeysSupport(id: string) {
+  return invoke<HotKeySupport>('hot_keys_support', { id })
+}
+
+export function findHotKeys(id: string, db: number, options: HotKeyOptions) {
+  return invoke<string>('find_hot_keys', { id, db, options })
+}
+
+export default {
+  hotKeysSupport,
+  findHotKeys,
+}
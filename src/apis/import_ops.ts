@@ -0,0 +1,10 @@
+import { ImportOptions } from '@/types/redis'
+import { invoke } from '@tauri-apps/api'
+
+export function importKeys(id: string, db: number, options: ImportOptions) {
+  return invoke<string>('import_keys', { id, db, options })
+}
+
+export default {
+  importKeys,
+}
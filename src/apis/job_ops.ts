@@ -0,0 +1,15 @@
+import { invoke } from '@tauri-apps/api'
+
+/** 后台任务事件名称 */
+export const JOB_EVENT = 'job'
+
+/** 后台任务产生数据的事件名称, 例如搜索到的匹配项 */
+export const JOB_DATA_EVENT = 'job-data'
+
+export function cancelJob(jobId: string) {
+  return invoke<boolean>('cancel_job', { jobId })
+}
+
+export default {
+  cancelJob,
+}
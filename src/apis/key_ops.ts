@@ -1,4 +1,4 @@
-import { KeyInfo, KeyContentDetail, AddKeyInfo } from '@/types/redis'
+import { KeyInfo, KeyContentDetail, AddKeyInfo, KeyExpire, CopyKeyInfo, ZRangeQuery, ZRangePage, KeyPreview, DeleteOptions } from '@/types/redis'
 import { invoke } from '@tauri-apps/api'
 
 export function getKeyType(id:string, db: number, key: string) {
@@ -10,7 +10,15 @@ export function delKey(id:string, db: number, key: string) {
 }
 
 export function delMatchKeys(id:string, db: number, matchKey: string) {
-  return invoke('del_match_key', { id, db, matchKey })
+  return invoke('del_match_keys', { id, db, matchKey })
+}
+
+export function previewMatchKeys(id:string, db: number, pattern: string, limit?: number) {
+  return invoke<KeyPreview>('preview_match_keys', { id, db, pattern, limit })
+}
+
+export function deleteMatchKeys(id:string, db: number, options: DeleteOptions) {
+  return invoke<string>('delete_match_keys', { id, db, options })
 }
 
 export function delKeyByValue(id:string, db: number, key: string, value?: string) {
@@ -29,14 +37,26 @@ export function getKeyInfo(id:string, db: number, key: string) {
   return invoke<KeyInfo>('get_key_info', { id, db, key })
 }
 
-export function getKeyDetail<T = any>(id:string, db: number, key: string) {
-  return invoke<KeyContentDetail<T>>('get_key_detail', { id, db, key })
+export function getKeyDetail<T = any>(id:string, db: number, key: string, search?: string) {
+  return invoke<KeyContentDetail<T>>('get_key_detail', { id, db, key, search })
+}
+
+export function getZSetRange(id:string, db: number, key: string, query: ZRangeQuery) {
+  return invoke<ZRangePage>('get_zset_range', { id, db, key, query })
 }
 
 export function renameKey(id:string, db: number, key: string, newKey: string) {
   return invoke('rename_key', { id, db, key, newKey })
 }
 
+export function copyKey(id:string, db: number, copyinfo: CopyKeyInfo) {
+  return invoke('copy_key', { id, db, copyinfo })
+}
+
+export function moveKey(id:string, db: number, key: string, targetDb: number) {
+  return invoke('move_key', { id, db, key, targetDb })
+}
+
 export function setKey(id:string, db: number, keyinfo: AddKeyInfo) {
   return invoke('set_key', { id, db, keyinfo })
 }
@@ -45,16 +65,26 @@ export function setKeyTTL(id:string, db: number, key: string, ttl: number) {
   return invoke('set_key_ttl', { id, db, key, ttl })
 }
 
+export function setKeyExpire(id:string, db: number, key: string, expire: KeyExpire) {
+  return invoke<number>('set_key_expire', { id, db, key, expire })
+}
+
 export default {
   getKeyType,
   delKey,
   delMatchKeys,
+  previewMatchKeys,
+  deleteMatchKeys,
   delKeyByValue,
   clearKeys,
   getKeysByDb,
   getKeyInfo,
   getKeyDetail,
+  getZSetRange,
   renameKey,
+  copyKey,
+  moveKey,
   setKey,
   setKeyTTL,
+  setKeyExpire,
 }
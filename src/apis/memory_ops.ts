@@ -0,0 +1,15 @@
+import { AnalyzeOptions, TtlOptions, TtlReport } from '@/types/redis'
+import { invoke } from '@tauri-apps/api'
+
+export function analyzeMemory(id: string, db: number, options: AnalyzeOptions) {
+  return invoke<string>('analyze_memory', { id, db, options })
+}
+
+export function ttlDistribution(id: string, db: number, options: TtlOptions) {
+  return invoke<TtlReport>('ttl_distribution', { id, db, options })
+}
+
+export default {
+  analyzeMemory,
+  ttlDistribution,
+}
@@ -0,0 +1,10 @@
+import { MigrateOptions } from '@/types/redis'
+import { invoke } from '@tauri-apps/api'
+
+export function migrateKeys(options: MigrateOptions) {
+  return invoke<string>('migrate_keys', { options })
+}
+
+export default {
+  migrateKeys,
+}
@@ -0,0 +1,15 @@
+import { NamespaceOptions, NamespacePreview } from '@/types/redis'
+import { invoke } from '@tauri-apps/api'
+
+export function previewNamespace(id: string, db: number, options: NamespaceOptions, limit?: number) {
+  return invoke<NamespacePreview>('preview_namespace', { id, db, options, limit })
+}
+
+export function runNamespaceOp(id: string, db: number, options: NamespaceOptions) {
+  return invoke<string>('run_namespace_op', { id, db, options })
+}
+
+export default {
+  previewNamespace,
+  runNamespaceOp,
+}
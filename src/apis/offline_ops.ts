@@ -0,0 +1,34 @@
+import {
+  MemoryReport,
+  MemoryReportOptions,
+  OfflineConfig,
+  RdbSummary,
+} from '@/types/redis'
+import { invoke } from '@tauri-apps/api'
+
+export function openRdb(config: OfflineConfig) {
+  return invoke<RdbSummary>('open_rdb', { config })
+}
+
+export function closeRdb(id: string) {
+  return invoke('close_rdb', { id })
+}
+
+export function rdbMemoryReport(id: string, options: MemoryReportOptions) {
+  return invoke<MemoryReport>('rdb_memory_report', { id, options })
+}
+
+export function exportRdbMemoryReport(
+  id: string,
+  db: number | undefined,
+  path: string
+) {
+  return invoke<number>('export_rdb_memory_report', { id, db, path })
+}
+
+export default {
+  openRdb,
+  closeRdb,
+  rdbMemoryReport,
+  exportRdbMemoryReport,
+}
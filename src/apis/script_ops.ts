@@ -0,0 +1,40 @@
+import { EvalRequest, EvalResult, SavedScript } from '@/types/redis'
+import { invoke } from '@tauri-apps/api'
+
+export function evalScript(id: string, db: number, request: EvalRequest) {
+  return invoke<EvalResult>('eval_script', { id, db, request })
+}
+
+export function scriptLoad(id: string, script: string) {
+  return invoke<string>('script_load', { id, script })
+}
+
+export function scriptExists(id: string, shas: string[]) {
+  return invoke<boolean[]>('script_exists', { id, shas })
+}
+
+export function scriptFlush(id: string, mode?: 'ASYNC' | 'SYNC') {
+  return invoke<void>('script_flush', { id, mode })
+}
+
+export function listScripts(id: string) {
+  return invoke<SavedScript[]>('list_scripts', { id })
+}
+
+export function saveScript(id: string, script: SavedScript) {
+  return invoke<SavedScript>('save_script', { id, script })
+}
+
+export function deleteScript(id: string, scriptId: number) {
+  return invoke<boolean>('delete_script', { id, scriptId })
+}
+
+export default {
+  evalScript,
+  scriptLoad,
+  scriptExists,
+  scriptFlush,
+  listScripts,
+  saveScript,
+  deleteScript,
+}
@@ -0,0 +1,10 @@
+import { SearchOptions } from '@/types/redis'
+import { invoke } from '@tauri-apps/api'
+
+export function searchValues(id: string, db: number, options: SearchOptions) {
+  return invoke<string>('search_values', { id, db, options })
+}
+
+export default {
+  searchValues,
+}
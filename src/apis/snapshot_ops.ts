@@ -0,0 +1,25 @@
+import { Snapshot, SnapshotSummary, VersionComparison } from '@/types/redis'
+import { invoke } from '@tauri-apps/api'
+
+export function listKeyVersions(id: string, db: number, key: string) {
+  return invoke<SnapshotSummary[]>('list_key_versions', { id, db, key })
+}
+
+export function getKeyVersion(id: string, version: number) {
+  return invoke<Snapshot>('get_key_version', { id, version })
+}
+
+export function diffKeyVersion(id: string, version: number, key?: string) {
+  return invoke<VersionComparison>('diff_key_version', { id, version, key })
+}
+
+export function restoreKeyVersion(id: string, version: number, key?: string) {
+  return invoke<void>('restore_key_version', { id, version, key })
+}
+
+export default {
+  listKeyVersions,
+  getKeyVersion,
+  diffKeyVersion,
+  restoreKeyVersion,
+}
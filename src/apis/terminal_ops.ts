@@ -1,5 +1,16 @@
+import { TerminalReply, TerminalSessionInfo } from '@/types/redis'
 import { invoke } from '@tauri-apps/api'
 
-export function sendTerminalCli(id:string, db: number, args?: string[]) {
-  return invoke<any>('terminal', { id, db, args })
+// 每个终端标签页使用独立的连接会话
+export function terminalOpen(id: string, db: number) {
+  return invoke<TerminalSessionInfo>('terminal_open', { id, db })
+}
+
+// line按redis-cli的规则在后端拆分参数, 没有line时使用已拆分的args
+export function sendTerminalCli(sessionId: string, line?: string, args?: string[]) {
+  return invoke<TerminalReply>('terminal', { sessionId, line, args })
+}
+
+export function terminalClose(sessionId: string) {
+  return invoke<boolean>('terminal_close', { sessionId })
 }
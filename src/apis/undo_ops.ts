@@ -0,0 +1,20 @@
+import { UndoReport, UndoSummary } from '@/types/redis'
+import { invoke } from '@tauri-apps/api'
+
+export function listUndo(id: string) {
+  return invoke<UndoSummary[]>('list_undo', { id })
+}
+
+export function undoLast(id: string) {
+  return invoke<UndoReport>('undo_last', { id })
+}
+
+export function restoreFromUndo(id: string, undoId: number) {
+  return invoke<UndoReport>('restore_from_undo', { id, undoId })
+}
+
+export default {
+  listUndo,
+  undoLast,
+  restoreFromUndo,
+}
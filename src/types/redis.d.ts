@@ -9,6 +9,14 @@ export interface RedisConfig {
   cluster: bool
 }
 
+// 离线连接(本地rdb文件)
+export interface OfflineConfig {
+  id: string
+  name: string
+  path: string
+  split: string
+}
+
 // redis 信息对象封装
 export interface Keyspace {
   db: number
@@ -39,13 +47,631 @@ export interface KeyInfo {
   label: string
   type: string
   ttl: number
+  pttl: number
+  expireAt?: number
+  encoding?: string
+  memory?: number
+  idleTime?: number
+  freq?: number
+  refcount?: number
+  elements?: number
+}
+
+export interface CopyKeyInfo {
+  key: string
+  newKey: string
+  targetDb?: number
+  replace?: boolean
 }
 
+export type KeyExpire =
+  | { type: 'persist' }
+  | { type: 'seconds', value: number }
+  | { type: 'milliseconds', value: number }
+  | { type: 'at', value: number }
+  | { type: 'atMilliseconds', value: number }
+
 export interface KeyContentDetail<T = any> {
   key: string
   label: string
   type: string
   ttl: number
+  pttl: number
+  expireAt?: number
   size: number
   value: T
 }
+
+// min和max与redis命令的参数格式一致, 例如 (1.5, -inf, [a, -
+export type ZRangeQuery = (
+  | { by: 'score', min: string, max: string }
+  | { by: 'lex', min: string, max: string }
+) & {
+  rev?: boolean
+  offset?: number
+  count?: number
+}
+
+export interface ZRangePage {
+  total: number
+  offset: number
+  hasMore: boolean
+  values: { score: number, member: string }[]
+}
+
+export type JobStatus = 'running' | 'finished' | 'cancelled' | 'failed'
+
+// 后台任务事件
+export interface JobEvent<T = any> {
+  jobId: string
+  kind: string
+  status: JobStatus
+  data?: T
+  error?: string
+}
+
+// 后台任务执行中产生的数据
+export interface JobData<T = any> {
+  jobId: string
+  kind: string
+  data: T
+}
+
+export interface KeyError {
+  key: string
+  message: string
+}
+
+export type ConflictPolicy = 'skip' | 'replace' | 'rename'
+
+export interface MigrateOptions {
+  sourceId: string
+  sourceDb: number
+  pattern: string
+  targetId: string
+  targetDb: number
+  conflict: ConflictPolicy
+  renameSuffix?: string
+  batchSize?: number
+  concurrency?: number
+}
+
+export interface MigrateProgress {
+  scanned: number
+  migrated: number
+  renamed: number
+  skipped: number
+  failed: number
+  failures: KeyError[]
+}
+
+export type ExportFormat = 'json' | 'csv' | 'resp'
+
+export interface ExportOptions {
+  pattern?: string
+  keys?: string[]
+  format: ExportFormat
+  path: string
+}
+
+export interface ExportReport {
+  exported: number
+  skipped: number
+  failed: number
+  failures: KeyError[]
+}
+
+export type ImportFormat = 'json' | 'csv' | 'resp' | 'cli'
+
+// csv的列名或从0开始的序号
+export type CsvColumn = string | number
+
+export interface CsvMapping {
+  keyColumn: CsvColumn
+  fieldColumn?: CsvColumn
+  valueColumn: CsvColumn
+  ttlColumn?: CsvColumn
+  hasHeaders?: boolean
+  delimiter?: string
+}
+
+export interface ImportOptions {
+  path: string
+  format: ImportFormat
+  dryRun?: boolean
+  batchSize?: number
+  csv?: CsvMapping
+}
+
+export interface LineError {
+  line: number
+  message: string
+}
+
+export interface ImportProgress {
+  dryRun: boolean
+  total: number
+  processed: number
+  commands: number
+  succeeded: number
+  failed: number
+  errors: LineError[]
+}
+
+export interface RdbDbSummary {
+  db: number
+  keys: number
+  expires: number
+}
+
+export interface RdbSummary {
+  version: number
+  aux: Record<string, string>
+  dbs: RdbDbSummary[]
+  skipped: number
+  // 非utf8的键转义后与已有的键重名而跳过的数量
+  collisions: number
+}
+
+export interface KeyMemory {
+  db: number
+  key: string
+  type: string
+  encoding?: string
+  bytes: number
+  elements: number
+  ttl: number
+}
+
+export interface GroupMemory {
+  name: string
+  keys: number
+  bytes: number
+  elements: number
+}
+
+export interface MemoryReport {
+  keys: number
+  bytes: number
+  byType: GroupMemory[]
+  byEncoding: GroupMemory[]
+  byPrefix: GroupMemory[]
+  byTtl: GroupMemory[]
+  topKeys: KeyMemory[]
+}
+
+export interface MemoryReportOptions {
+  db?: number
+  top?: number
+  depth?: number
+}
+
+export interface AnalyzeOptions {
+  pattern?: string
+  top?: number
+  depth?: number
+  scanCount?: number
+  delay?: number
+}
+
+export interface AnalyzeProgress {
+  nodes: number
+  totalNodes: number
+  keys: number
+  bytes: number
+}
+
+export type HotKeyMethod = 'lfu' | 'monitor'
+
+export interface HotKeySupport {
+  policy?: string
+  lfu: boolean
+  message?: string
+}
+
+export interface HotKeyOptions {
+  method: HotKeyMethod
+  pattern?: string
+  top?: number
+  depth?: number
+  scanCount?: number
+  delay?: number
+  duration?: number
+}
+
+export interface HotKey {
+  key: string
+  hits: number
+}
+
+export interface HotKeyGroup {
+  name: string
+  keys: number
+  hits: number
+  top: HotKey[]
+}
+
+export interface HotKeyProgress {
+  scanned: number
+  elapsed: number
+}
+
+export interface HotKeyReport {
+  method: HotKeyMethod
+  scanned: number
+  elapsed: number
+  keys: HotKey[]
+  byPrefix: HotKeyGroup[]
+}
+
+export interface TtlOptions {
+  sample?: number
+  hours?: number
+  depth?: number
+}
+
+export interface ExpiryForecast {
+  hour: number
+  keys: number
+  bytes: number
+}
+
+export interface TtlReport {
+  total: number
+  sampled: number
+  memory: boolean
+  buckets: GroupMemory[]
+  noTtl: GroupMemory[]
+  forecast: ExpiryForecast[]
+}
+
+export interface SearchOptions {
+  pattern?: string
+  query: string
+  regex?: boolean
+  ignoreCase?: boolean
+  scanCount?: number
+  delay?: number
+  maxMatches?: number
+}
+
+export type MatchLocation =
+  | { kind: 'value' }
+  | { kind: 'index', index: number }
+  | { kind: 'member', score?: number }
+  | { kind: 'field', field: string }
+  | { kind: 'hashValue', field: string }
+  | { kind: 'entry', id: string, field: string }
+
+export interface ValueMatch {
+  key: string
+  type: string
+  location: MatchLocation
+  value: string
+}
+
+export interface SearchProgress {
+  scanned: number
+  keys: number
+  matches: number
+  failed: number
+  truncated: boolean
+}
+
+export type NamespaceOp =
+  | { op: 'rename', to: string, replace?: boolean }
+  | { op: 'expire', expire: KeyExpire }
+  | { op: 'stats' }
+
+export type NamespaceOptions = NamespaceOp & {
+  prefix: string
+  batchSize?: number
+  delay?: number
+}
+
+export interface NamespaceKey {
+  key: string
+  newKey?: string
+}
+
+export interface NamespacePreview {
+  count: number
+  keys: NamespaceKey[]
+}
+
+export interface NamespaceProgress {
+  scanned: number
+  processed: number
+  skipped: number
+  failed: number
+  bytes?: number
+  failures: KeyError[]
+}
+
+export interface KeyPreview {
+  count: number
+  keys: string[]
+}
+
+export interface DeleteOptions {
+  pattern: string
+  batchSize?: number
+  delay?: number
+  maxKeys?: number
+}
+
+export interface DeleteProgress {
+  scanned: number
+  deleted: number
+}
+
+export interface UndoSummary {
+  id: number
+  op: string
+  db: number
+  createdAt: number
+  count: number
+  bytes: number
+  truncated: boolean
+  keys: string[]
+}
+
+export interface UndoReport {
+  id: number
+  restored: number
+  expired: number
+  failed: number
+  failures: KeyError[]
+}
+
+// element: 字符串为空, 哈希为字段, 集合和有序集合为成员, 列表为下标, stream为消息id
+// source/target: 集合为成员本身, 有序集合为分数, stream为消息内容的json, 为空表示只存在于另一边
+export interface ElementDiff {
+  element: string
+  source?: string
+  target?: string
+}
+
+export interface ValueDiff {
+  type: string
+  changes: ElementDiff[]
+  truncated: boolean
+}
+
+export interface DiffOptions {
+  sourceId: string
+  sourceDb: number
+  targetId: string
+  targetDb: number
+  pattern: string
+  ttlTolerance?: number
+  scanCount?: number
+  delay?: number
+}
+
+export type DiffKind = 'onlyInSource' | 'onlyInTarget' | 'type' | 'ttl' | 'value'
+
+export interface KeyDiff {
+  key: string
+  kind: DiffKind
+  sourceType?: string
+  targetType?: string
+  sourceTtl?: number
+  targetTtl?: number
+  diff?: ValueDiff
+}
+
+export interface DiffProgress {
+  sourceScanned: number
+  targetScanned: number
+  equal: number
+  onlyInSource: number
+  onlyInTarget: number
+  typeDiff: number
+  ttlDiff: number
+  valueDiff: number
+  failed: number
+}
+
+export interface SyncOptions {
+  sourceId: string
+  sourceDb: number
+  targetId: string
+  targetDb: number
+  keys: string[]
+}
+
+export interface SyncProgress {
+  total: number
+  written: number
+  deleted: number
+  failed: number
+  failures: KeyError[]
+}
+
+export interface KeyLocation {
+  id: string
+  db: number
+  key: string
+}
+
+// 行号从1开始
+export interface LineDiff {
+  tag: 'equal' | 'delete' | 'insert'
+  sourceLine?: number
+  targetLine?: number
+  text: string
+}
+
+// lines: 字符串按行比较, json为true时两边都按格式化后的json比较
+export type ValueComparison =
+  | { kind: 'type' }
+  | { kind: 'missing' }
+  | { kind: 'lines', json: boolean, equal: boolean, lines: LineDiff[], truncated: boolean }
+  | ({ kind: 'elements' } & ValueDiff)
+
+export interface KeyComparison {
+  source: KeyContentDetail
+  target: KeyContentDetail
+  equal: boolean
+  diff: ValueComparison
+}
+
+// 键的完整数据, 与导入导出的json格式一致
+export type KeyRecord = {
+  key: string
+  // 剩余过期时间(毫秒)
+  pttl: number
+} & (
+  | { type: 'string', value: string }
+  | { type: 'list' | 'set', value: string[] }
+  | { type: 'zset', value: { score: number, member: string }[] }
+  | { type: 'hash', value: Record<string, string> }
+  | { type: 'stream', value: { id: string, fields: Record<string, string> }[] }
+)
+
+export interface SnapshotSummary {
+  id: number
+  db: number
+  key: string
+  op: string
+  createdAt: number
+  type: string
+  size: number
+  bytes: number
+}
+
+export interface Snapshot {
+  id: number
+  db: number
+  op: string
+  createdAt: number
+  bytes: number
+  record: KeyRecord
+}
+
+export interface VersionComparison {
+  version: Snapshot
+  current?: KeyRecord
+  equal: boolean
+  diff: ValueComparison
+}
+
+// 带类型的命令返回值, bulk不是utf8编码时text为redis-cli格式的转义字符串, bytes为原始字节
+// repr总是redis-cli格式的转义字符串, 不包含两边的引号
+export type RespValue =
+  | { type: 'nil' }
+  | { type: 'integer', value: number }
+  | { type: 'bulk', text: string, repr: string, bytes?: number[] }
+  | { type: 'array', values: RespValue[] }
+  | { type: 'status', value: string }
+  | { type: 'error', code: string, message: string }
+
+// 有script时先尝试EVALSHA, 脚本未缓存时使用EVAL
+export interface EvalRequest {
+  script?: string
+  sha?: string
+  keys?: string[]
+  args?: string[]
+}
+
+export interface EvalResult {
+  sha: string
+  result: RespValue
+  elapsed: number
+}
+
+// id为0时新建
+export interface SavedScript {
+  id: number
+  name: string
+  script: string
+  keys?: string[]
+  args?: string[]
+  updatedAt?: number
+}
+
+// yes: 在子进程中调试, 结束后回滚数据的修改; sync: 阻塞服务端, 保留数据的修改
+export type DebugMode = 'yes' | 'sync'
+
+export interface DebugRequest {
+  script: string
+  keys?: string[]
+  args?: string[]
+  mode: DebugMode
+}
+
+// 与redis-cli --ldb中的命令对应, delete的line为空时删除所有断点
+export type DebugCommand =
+  | { cmd: 'step' }
+  | { cmd: 'continue' }
+  | { cmd: 'abort' }
+  | { cmd: 'break', line: number }
+  | { cmd: 'delete', line?: number }
+  | { cmd: 'list', line?: number, context?: number }
+  | { cmd: 'whole' }
+  | { cmd: 'print', var?: string }
+  | { cmd: 'eval', code: string }
+  | { cmd: 'redis', args: string[] }
+  | { cmd: 'trace' }
+  | { cmd: 'maxlen', len?: number }
+
+export interface DebugReply {
+  sessionId: string
+  lines: string[]
+  line?: number
+  breakpoints: number[]
+  ended: boolean
+  result?: RespValue
+}
+
+export interface DebugSessionInfo {
+  sessionId: string
+  id: string
+  db: number
+  mode: DebugMode
+  line?: number
+  breakpoints: number[]
+  startedAt: number
+}
+
+export interface FunctionInfo {
+  name: string
+  description?: string
+  flags: string[]
+}
+
+// code只有withCode时才有
+export interface FunctionLibrary {
+  name: string
+  engine: string
+  functions: FunctionInfo[]
+  code?: string
+}
+
+// readOnly时使用FCALL_RO
+export interface FcallRequest {
+  function: string
+  keys?: string[]
+  args?: string[]
+  readOnly?: boolean
+}
+
+// APPEND遇到同名库时报错, REPLACE覆盖同名库, FLUSH先删除所有库
+export type FunctionRestorePolicy = 'APPEND' | 'REPLACE' | 'FLUSH'
+
+// 终端会话的状态, 每次执行命令后返回
+export interface TerminalSessionInfo {
+  sessionId: string
+  id: string
+  db: number
+  transaction: boolean
+  queued: number
+  user: string
+  startedAt: number
+}
+
+export interface TerminalReply {
+  result: RespValue
+  session: TerminalSessionInfo
+}
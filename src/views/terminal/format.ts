@@ -0,0 +1,27 @@
+import { RespValue } from '@/types/redis'
+
+// 按redis-cli的格式输出返回值, 嵌套数组的后续行与序号对齐
+export const formatResp = (value: RespValue): string[] => {
+  switch (value.type) {
+  case 'nil':
+    return ['(nil)']
+  case 'integer':
+    return [`(integer) ${value.value}`]
+  case 'bulk':
+    return [`"${value.repr}"`]
+  case 'status':
+    return [value.value]
+  case 'error':
+    return [`(error) ${value.code} ${value.message}`]
+  case 'array': {
+    if (value.values.length === 0) {
+      return ['(empty array)']
+    }
+    const width = String(value.values.length).length
+    return value.values.flatMap((item, index) => {
+      const prefix = `${String(index + 1).padStart(width)}) `
+      return formatResp(item).map((line, i) => (i === 0 ? prefix : ' '.repeat(prefix.length)) + line)
+    })
+  }
+  }
+}